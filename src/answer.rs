@@ -0,0 +1,50 @@
+use std::fmt;
+
+use crate::point::Point;
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Str(String),
+    Coord(i64, i64),
+    /// The part has no computed answer
+    Unsolved,
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(n) => write!(f, "{}", n),
+            Answer::Str(s) => write!(f, "{}", s),
+            Answer::Coord(x, y) => write!(f, "{},{}", x, y),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer::Int(i64::try_from(n).expect("Answer should fit in an i64"))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i32, i64, isize, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Self {
+        Answer::Str(s)
+    }
+}
+
+impl From<Point> for Answer {
+    fn from(point: Point) -> Self {
+        Answer::Coord(point.x as i64, point.y as i64)
+    }
+}
@@ -0,0 +1,66 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Command line arguments split into positionals, `--option value` pairs and `--switch`es.
+#[derive(Debug, Default)]
+pub struct Args {
+    pub positional: Vec<String>,
+    options: HashMap<String, Vec<String>>,
+    switches: Vec<String>,
+}
+
+impl Args {
+    /// Split `args`, accepting only the given options (which take a value) and switches.
+    pub fn parse(args: &[String], options: &[&str], switches: &[&str]) -> Result<Args, String> {
+        let mut parsed = Args::default();
+        let mut args = args.iter();
+
+        while let Some(arg) = args.next() {
+            if options.contains(&arg.as_str()) {
+                let value = args
+                    .next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))?;
+                parsed
+                    .options
+                    .entry(arg.clone())
+                    .or_default()
+                    .push(value.clone());
+            } else if switches.contains(&arg.as_str()) {
+                parsed.switches.push(arg.clone());
+            } else if arg.starts_with("--") {
+                return Err(format!("Unknown option: {}", arg));
+            } else {
+                parsed.positional.push(arg.clone());
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn switch(&self, name: &str) -> bool {
+        self.switches.iter().any(|s| s == name)
+    }
+
+    /// The last value given for an option.
+    pub fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .get(name)
+            .and_then(|values| values.last())
+            .map(String::as_str)
+    }
+
+    /// Every value given for an option, in order.
+    pub fn option_values(&self, name: &str) -> &[String] {
+        self.options.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Parse the last value given for an option, falling back to `default`.
+    pub fn option_or<T: FromStr>(&self, name: &str, default: T) -> Result<T, String> {
+        match self.option(name) {
+            None => Ok(default),
+            Some(value) => value
+                .parse()
+                .map_err(|_| format!("Invalid value for {}: {}", name, value)),
+        }
+    }
+}
@@ -0,0 +1,250 @@
+use std::hint::black_box;
+use std::time::Duration;
+
+use crate::json::Value;
+use crate::params::AnyParams;
+use crate::parser::ParseError;
+use crate::runner::{print_table, timed};
+use crate::solution::ParseFn;
+
+/// How many times to run each stage of a benchmark.
+#[derive(Debug, Clone, Copy)]
+pub struct Config {
+    pub warmup: usize,
+    pub runs: usize,
+}
+
+/// Summary statistics over a set of timing samples.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub std_dev: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &mut [Duration]) -> Stats {
+        assert!(!samples.is_empty(), "Need at least one sample");
+        samples.sort_unstable();
+
+        let n = samples.len();
+        let median = if n.is_multiple_of(2) {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        } else {
+            samples[n / 2]
+        };
+
+        let secs: Vec<f64> = samples.iter().map(Duration::as_secs_f64).collect();
+        let mean = secs.iter().sum::<f64>() / n as f64;
+        // Sample standard deviation
+        let variance = if n > 1 {
+            secs.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Stats {
+            min: samples[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            std_dev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+
+    fn to_json(self) -> Value {
+        let nanos = |d: Duration| Value::Number(d.as_nanos() as f64);
+        Value::Object(vec![
+            ("min_ns".to_string(), nanos(self.min)),
+            ("median_ns".to_string(), nanos(self.median)),
+            ("mean_ns".to_string(), nanos(self.mean)),
+            ("std_dev_ns".to_string(), nanos(self.std_dev)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Stats> {
+        let nanos = |key| Some(Duration::from_nanos(value.get(key)?.as_f64()? as u64));
+        Some(Stats {
+            min: nanos("min_ns")?,
+            median: nanos("median_ns")?,
+            mean: nanos("mean_ns")?,
+            std_dev: nanos("std_dev_ns")?,
+        })
+    }
+}
+
+pub const STAGES: [&str; 3] = ["parse", "part1", "part2"];
+
+/// Benchmark results for one day, in [`STAGES`] order.
+#[derive(Debug, Clone)]
+pub struct DayBench {
+    pub day: usize,
+    pub stages: [Stats; 3],
+}
+
+fn sample(config: Config, mut f: impl FnMut()) -> Stats {
+    for _ in 0..config.warmup {
+        f();
+    }
+
+    let mut samples: Vec<_> = (0..config.runs.max(1)).map(|_| timed(&mut f).1).collect();
+    Stats::from_samples(&mut samples)
+}
+
+pub fn bench_day(
+    day: usize,
+    parse: ParseFn,
+    params: &AnyParams,
+    input: &str,
+    config: Config,
+) -> Result<DayBench, ParseError> {
+    // Bail out before sampling if the input doesn't parse
+    let solver = parse(input, params).map_err(|err| err.with_day(day))?;
+    let parse_stats = sample(config, || {
+        let _ = black_box(parse(black_box(input), params));
+    });
+
+    let part1 = sample(config, || {
+        black_box(solver.part1());
+    });
+    let part2 = sample(config, || {
+        black_box(solver.part2());
+    });
+
+    Ok(DayBench {
+        day,
+        stages: [parse_stats, part1, part2],
+    })
+}
+
+pub fn to_json(results: &[DayBench], config: Config) -> Value {
+    let days = results
+        .iter()
+        .map(|result| {
+            let stages = STAGES
+                .iter()
+                .zip(result.stages)
+                .map(|(name, stats)| (name.to_string(), stats.to_json()))
+                .collect();
+            (result.day.to_string(), Value::Object(stages))
+        })
+        .collect();
+
+    Value::Object(vec![
+        ("warmup".to_string(), Value::Number(config.warmup as f64)),
+        ("runs".to_string(), Value::Number(config.runs as f64)),
+        ("days".to_string(), Value::Object(days)),
+    ])
+}
+
+/// Read back results saved by [`to_json`].
+pub fn from_json(value: &Value) -> Result<Vec<DayBench>, String> {
+    let days = value
+        .get("days")
+        .and_then(Value::as_object)
+        .ok_or("Missing \"days\" object")?;
+
+    days.iter()
+        .map(|(day, stages)| {
+            let day = day.parse().map_err(|_| format!("Invalid day: {}", day))?;
+            let stage = |name| {
+                stages
+                    .get(name)
+                    .and_then(Stats::from_json)
+                    .ok_or_else(|| format!("Missing or invalid {} stats for day {}", name, day))
+            };
+            Ok(DayBench {
+                day,
+                stages: [stage("parse")?, stage("part1")?, stage("part2")?],
+            })
+        })
+        .collect()
+}
+
+/// Print results, comparing medians against `baseline` if given.
+///
+/// Returns true if any stage's median slowed down by more than `threshold` (a fraction).
+pub fn report(results: &[DayBench], baseline: Option<&[DayBench]>, threshold: f64) -> bool {
+    let mut header = vec!["Day", "Stage", "Min", "Median", "Mean", "Std dev"];
+    if baseline.is_some() {
+        header.extend(["Baseline", "Change", ""]);
+    }
+
+    let mut rows = vec![header.into_iter().map(String::from).collect::<Vec<_>>()];
+    let mut regressed = false;
+
+    for result in results {
+        let base = baseline.and_then(|b| b.iter().find(|b| b.day == result.day));
+
+        for (i, (name, stats)) in STAGES.iter().zip(result.stages).enumerate() {
+            // Only label the first row of each day
+            let day = if i == 0 {
+                result.day.to_string()
+            } else {
+                String::new()
+            };
+            let mut row = vec![
+                day,
+                name.to_string(),
+                format!("{:.2?}", stats.min),
+                format!("{:.2?}", stats.median),
+                format!("{:.2?}", stats.mean),
+                format!("{:.2?}", stats.std_dev),
+            ];
+
+            if let Some(base) = base {
+                let old = base.stages[i].median.as_secs_f64();
+                let change = (stats.median.as_secs_f64() - old) / old;
+                let slower = old > 0.0 && change > threshold;
+                regressed |= slower;
+
+                row.push(format!("{:.2?}", base.stages[i].median));
+                row.push(if old > 0.0 {
+                    format!("{:+.1}%", change * 100.0)
+                } else {
+                    String::from("-")
+                });
+                if slower {
+                    row.push(String::from("REGRESSION"));
+                }
+            }
+
+            rows.push(row);
+        }
+    }
+
+    print_table(&rows, &[1, 8]);
+    regressed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let mut samples = [4, 1, 3, 2].map(Duration::from_millis);
+        let stats = Stats::from_samples(&mut samples);
+
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        // sqrt(5/3) ms
+        assert_eq!(stats.std_dev.as_micros(), 1290);
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let stats = Stats::from_samples(&mut [5, 7, 9].map(Duration::from_micros));
+        let results = vec![DayBench {
+            day: 6,
+            stages: [stats; 3],
+        }];
+        let config = Config { warmup: 1, runs: 3 };
+
+        let parsed = from_json(&to_json(&results, config)).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].day, 6);
+        assert_eq!(parsed[0].stages, [stats; 3]);
+    }
+}
@@ -0,0 +1,203 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const YEAR: u32 = 2024;
+
+/// Environment variables checked before the defaults.
+pub const SESSION_VAR: &str = "AOC_SESSION";
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+const USER_AGENT: &str = "aoc24 (github.com/rufusutt/aoc24)";
+
+/// Where the session token is read from when it isn't in the environment.
+pub fn session_path() -> Option<PathBuf> {
+    let config = env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))?;
+    Some(config.join("aoc24").join("session"))
+}
+
+/// Find the session token, from `AOC_SESSION` or the config file.
+pub fn find_session() -> Result<String, String> {
+    if let Ok(session) = env::var(SESSION_VAR) {
+        return Ok(session.trim().to_string());
+    }
+
+    let path = session_path().ok_or_else(|| format!("{} isn't set", SESSION_VAR))?;
+    match fs::read_to_string(&path) {
+        Ok(session) => Ok(session.trim().to_string()),
+        Err(_) => Err(format!(
+            "No session token, set {} or put it in {}",
+            SESSION_VAR,
+            path.display()
+        )),
+    }
+}
+
+/// Talks to the Advent of Code site, or anything pretending to be it.
+///
+/// Requests go through `curl`, so there's no TLS stack to build.
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub base_url: String,
+    session: String,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+        }
+    }
+
+    pub fn day_url(&self, day: usize) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    /// Download a day's puzzle input.
+    pub fn input(&self, day: usize) -> Result<String, String> {
+        self.request(&format!("{}/input", self.day_url(day)), &[])
+    }
+
+    /// Submit an answer, returning the response page.
+    pub fn submit(&self, day: usize, part: u8, answer: &str) -> Result<String, String> {
+        let level = part.to_string();
+        self.request(
+            &format!("{}/answer", self.day_url(day)),
+            &[("level", &level), ("answer", answer)],
+        )
+    }
+
+    /// Make a request, as a POST if there's any form data, returning the response body.
+    fn request(&self, url: &str, form: &[(&str, &str)]) -> Result<String, String> {
+        let mut command = Command::new("curl");
+        command
+            .args(["--silent", "--show-error", "--fail"])
+            .args(["--user-agent", USER_AGENT])
+            // Read the cookie from stdin so the token doesn't show up in the process list
+            .args(["--header", "@-"])
+            .arg(url)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        for (key, value) in form {
+            command
+                .arg("--data-urlencode")
+                .arg(format!("{}={}", key, value));
+        }
+
+        let mut child = command
+            .spawn()
+            .map_err(|err| format!("Couldn't run curl: {}", err))?;
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        writeln!(stdin, "Cookie: session={}", self.session)
+            .map_err(|err| format!("Couldn't run curl: {}", err))?;
+        drop(stdin);
+
+        let output = child
+            .wait_with_output()
+            .map_err(|err| format!("Couldn't run curl: {}", err))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Request to {} failed: {}", url, stderr.trim()));
+        }
+        String::from_utf8(output.stdout).map_err(|_| format!("Response from {} isn't UTF-8", url))
+    }
+}
+
+/// Whether [`fetch`] had to download anything.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fetched {
+    Cached,
+    Downloaded,
+}
+
+/// Make sure a day's input is at `path`, downloading it only if it isn't there already.
+pub fn fetch(client: &Client, day: usize, path: &Path) -> Result<Fetched, String> {
+    if path.exists() {
+        return Ok(Fetched::Cached);
+    }
+
+    let input = client.input(day)?;
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|err| format!("{}: {}", dir.display(), err))?;
+    }
+
+    // Write then rename, so an interrupted download isn't mistaken for a cached input
+    let partial = path.with_extension("part");
+    fs::write(&partial, input)
+        .and_then(|()| fs::rename(&partial, path))
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    Ok(Fetched::Downloaded)
+}
+
+/// Serve one request with `body` on a local port, returning the URL to reach it on and the
+/// request's headers and body, so [`Client`] can be tested without the real site.
+#[cfg(test)]
+pub(crate) fn serve_once(
+    body: &'static str,
+) -> (String, std::thread::JoinHandle<(Vec<String>, String)>) {
+    use std::io::{BufRead, BufReader, Read};
+    use std::net::TcpListener;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(&stream);
+        let headers: Vec<String> = reader
+            .by_ref()
+            .lines()
+            .map(Result::unwrap)
+            .take_while(|line| !line.is_empty())
+            .collect();
+        let length = headers
+            .iter()
+            .find_map(|header| header.strip_prefix("Content-Length: "))
+            .map_or(0, |length| length.parse().unwrap());
+        let mut request = String::new();
+        reader.take(length).read_to_string(&mut request).unwrap();
+
+        write!(
+            stream,
+            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        )
+        .unwrap();
+        (headers, request)
+    });
+
+    (url, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fetch_caches() {
+        let (url, server) = serve_once("1 2\n3 4\n");
+        let client = Client::new(&url, "abc123");
+        let dir = env::temp_dir().join(format!("aoc24-fetch-{}", std::process::id()));
+        let path = dir.join("day01.txt");
+
+        assert_eq!(fetch(&client, 1, &path), Ok(Fetched::Downloaded));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1 2\n3 4\n");
+
+        let (headers, _) = server.join().unwrap();
+        assert_eq!(headers[0], "GET /2024/day/1/input HTTP/1.1");
+        assert!(headers.contains(&"Cookie: session=abc123".to_string()));
+
+        // The server has gone, so this must not try to download again
+        assert_eq!(fetch(&client, 1, &path), Ok(Fetched::Cached));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
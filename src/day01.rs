@@ -1,23 +1,48 @@
-pub fn solution(input: &str) {
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
+pub struct Lists {
+    pub left: Vec<i64>,
+    pub right: Vec<i64>,
+}
+
+pub fn parse(input: &str) -> Result<Lists, ParseError> {
+    let p = Parser::new(input);
+
     // Parse input into left and right columns
-    let (mut left, mut right): (Vec<_>, Vec<_>) = input
+    let (mut left, mut right): (Vec<_>, Vec<_>) = p
         .lines()
-        .map(|line| {
-            let mut parts = line.split_whitespace().map(|p| p.parse::<i64>().unwrap());
-            (parts.next().unwrap(), parts.next().unwrap())
+        .map(|line| match p.numbers::<i64>(line)?[..] {
+            [l, r] => Ok((l, r)),
+            _ => Err(p.error(line, "two numbers")),
         })
+        .collect::<Result<Vec<_>, _>>()?
+        .into_iter()
         .unzip();
 
     // Sort each column independently
     left.sort_unstable();
     right.sort_unstable();
 
+    Ok(Lists { left, right })
+}
+
+pub fn part1(lists: &Lists) -> Answer {
     // Sum of differences between sorted columns
-    let sum: i64 = left.iter().zip(&right).map(|(l, r)| (l - r).abs()).sum();
-    println!("Part 1: {}", sum);
+    let sum: i64 = lists
+        .left
+        .iter()
+        .zip(&lists.right)
+        .map(|(l, r)| (l - r).abs())
+        .sum();
+    sum.into()
+}
 
+pub fn part2(lists: &Lists) -> Answer {
     // Map of occurrences of each number in the right column
-    let right_map = right
+    let right_map = lists
+        .right
         .iter()
         .fold(std::collections::HashMap::new(), |mut acc, &r| {
             *acc.entry(r).or_insert(0) += 1;
@@ -26,9 +51,69 @@ pub fn solution(input: &str) {
 
     // "Similarity score"
     // The number in left column multiplied by occurrences in right column
-    let score: i64 = left
+    let score: i64 = lists
+        .left
         .iter()
         .map(|l| l * right_map.get(l).unwrap_or(&0))
         .sum();
-    println!("Part 2: {}", score);
+    score.into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+",
+    params: &[],
+    part1: Some("11"),
+    part2: Some("31"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    /// The columns in the order they're given
+    fn columns(input: &str) -> (Vec<i64>, Vec<i64>) {
+        input
+            .lines()
+            .map(|line| {
+                let mut numbers = line.split_whitespace().map(|n| n.parse::<i64>().unwrap());
+                (numbers.next().unwrap(), numbers.next().unwrap())
+            })
+            .unzip()
+    }
+
+    // Pair off the smallest numbers left in each column, one pair at a time
+    fn oracle1(input: &str) -> i64 {
+        let (mut left, mut right) = columns(input);
+        let mut total = 0;
+        while !left.is_empty() {
+            let l = left.swap_remove((0..left.len()).min_by_key(|&i| left[i]).unwrap());
+            let r = right.swap_remove((0..right.len()).min_by_key(|&i| right[i]).unwrap());
+            total += (l - r).abs();
+        }
+        total
+    }
+
+    fn oracle2(input: &str) -> i64 {
+        let (left, right) = columns(input);
+        left.iter()
+            .map(|l| l * right.iter().filter(|&r| r == l).count() as i64)
+            .sum()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(1, 50, 20) {
+            let lists = parse(&input).unwrap();
+            assert_eq!(part1(&lists), oracle1(&input).into(), "seed {}", seed);
+            assert_eq!(part2(&lists), oracle2(&input).into(), "seed {}", seed);
+        }
+    }
 }
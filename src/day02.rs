@@ -1,3 +1,7 @@
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 fn valid_delta(delta: i64) -> bool {
     (1..=3).contains(&delta.abs())
 }
@@ -43,25 +47,95 @@ fn dampened_safe_report(levels: &[i64]) -> bool {
     false
 }
 
-pub fn solution(input: &str) {
-    let (safe_count, dampened_safe_count) = input
+pub struct Reports {
+    pub reports: Vec<Vec<i64>>,
+}
+
+pub fn parse(input: &str) -> Result<Reports, ParseError> {
+    let p = Parser::new(input);
+
+    // Parse each report into levels
+    let reports = p
         .lines()
-        .map(|report| {
-            // Parse report into levels
-            let levels: Vec<i64> = report
-                .split_whitespace()
-                .map(|l| l.parse().unwrap())
-                .collect();
-
-            (
-                safe_report(&levels) as i64,
-                dampened_safe_report(&levels) as i64,
-            )
-        })
-        .fold((0, 0), |(safe_acc, dampened_acc), (safe, dampened)| {
-            (safe_acc + safe, dampened_acc + dampened)
-        });
+        .map(|report| p.numbers(report))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Reports { reports })
+}
+
+pub fn part1(reports: &Reports) -> Answer {
+    let safe_count = reports
+        .reports
+        .iter()
+        .filter(|levels| safe_report(levels))
+        .count();
+    safe_count.into()
+}
+
+pub fn part2(reports: &Reports) -> Answer {
+    let dampened_safe_count = reports
+        .reports
+        .iter()
+        .filter(|levels| dampened_safe_report(levels))
+        .count();
+    dampened_safe_count.into()
+}
 
-    println!("Part 1: {}", safe_count);
-    println!("Part 2: {}", dampened_safe_count);
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9
+",
+    params: &[],
+    part1: Some("2"),
+    part2: Some("4"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    // Safe reports climb by 1 to 3 each step, one way or the other
+    fn safe(levels: &[i64]) -> bool {
+        let climbs = |levels: &[i64]| levels.windows(2).all(|w| (1..=3).contains(&(w[1] - w[0])));
+        let reversed: Vec<i64> = levels.iter().rev().copied().collect();
+        climbs(levels) || climbs(&reversed)
+    }
+
+    fn oracle1(reports: &Reports) -> usize {
+        reports.reports.iter().filter(|levels| safe(levels)).count()
+    }
+
+    fn oracle2(reports: &Reports) -> usize {
+        reports
+            .reports
+            .iter()
+            .filter(|levels| {
+                safe(levels)
+                    || (0..levels.len()).any(|skip| {
+                        let dampened: Vec<i64> = levels
+                            .iter()
+                            .enumerate()
+                            .filter(|&(i, _)| i != skip)
+                            .map(|(_, &level)| level)
+                            .collect();
+                        safe(&dampened)
+                    })
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(2, 50, 20) {
+            let reports = parse(&input).unwrap();
+            assert_eq!(part1(&reports), oracle1(&reports).into(), "seed {}", seed);
+            assert_eq!(part2(&reports), oracle2(&reports).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,45 +1,138 @@
 use regex::Regex;
 
-pub fn solution(input: &str) {
-    let re = Regex::new(r"mul\((\d+),(\d+)\)").unwrap();
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
 
-    let part1: i64 = re
+pub enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
+}
+
+pub struct Program {
+    pub instructions: Vec<Instruction>,
+}
+
+pub fn parse(input: &str) -> Result<Program, ParseError> {
+    let p = Parser::new(input);
+    let re = Regex::new(r"mul\((\d+),(\d+)\)|do\(\)|don't\(\)").unwrap();
+
+    let instructions = re
         .captures_iter(input)
-        .map(|captures| {
-            let first: i64 = captures[1].parse().unwrap();
-            let second: i64 = captures[2].parse().unwrap();
-            first * second
+        .map(|captures| match &captures[0] {
+            "do()" => Ok(Instruction::Do),
+            "don't()" => Ok(Instruction::Dont),
+            _ => {
+                // The regex only matches digits, but they can still overflow
+                let first: i64 = p.number(&captures[1])?;
+                let second: i64 = p.number(&captures[2])?;
+                Ok(Instruction::Mul(first, second))
+            }
         })
-        .sum();
-    println!("Part 1: {}", part1);
+        .collect::<Result<_, _>>()?;
 
-    let re = Regex::new(r"((?:mul|do|don't))\(((?:\d+,\d+)?)\)").unwrap();
+    Ok(Program { instructions })
+}
+
+pub fn part1(program: &Program) -> Answer {
+    let part1: i64 = program
+        .instructions
+        .iter()
+        .map(|instruction| match instruction {
+            Instruction::Mul(first, second) => first * second,
+            _ => 0,
+        })
+        .sum();
+    part1.into()
+}
 
+pub fn part2(program: &Program) -> Answer {
     let mut enabled = true;
-    let part2: i64 = re
-        .captures_iter(input)
-        .filter_map(|captures| {
-            let command = &captures[1];
-            let args = &captures[2];
-
-            match command {
-                "mul" if enabled => {
-                    let mut numbers = args.split(',').filter_map(|n| n.parse::<i64>().ok());
-                    let first = numbers.next().unwrap();
-                    let second = numbers.next().unwrap();
-                    Some(first * second)
-                }
-                "do" => {
-                    enabled = true;
-                    None
-                }
-                "don't" => {
-                    enabled = false;
-                    None
-                }
-                _ => None,
+    let part2: i64 = program
+        .instructions
+        .iter()
+        .filter_map(|instruction| match instruction {
+            Instruction::Mul(first, second) if enabled => Some(first * second),
+            Instruction::Do => {
+                enabled = true;
+                None
+            }
+            Instruction::Dont => {
+                enabled = false;
+                None
             }
+            _ => None,
         })
         .sum();
-    println!("Part 2: {}", part2);
+    part2.into()
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))
+",
+        params: &[],
+        part1: Some("161"),
+        part2: None,
+    },
+    Example {
+        input: "\
+xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))
+",
+        params: &[],
+        part1: None,
+        part2: Some("48"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    /// The number of digits at the start of `s`, and their value.
+    fn digits(s: &[u8]) -> (usize, i64) {
+        let len = s.iter().take_while(|b| b.is_ascii_digit()).count();
+        let value = s[..len]
+            .iter()
+            .fold(0, |n, &b| n * 10 + i64::from(b - b'0'));
+        (len, value)
+    }
+
+    // Try to read a `mul(a,b)` starting at every byte, by hand rather than with a regex
+    fn oracle(input: &str, obey: bool) -> i64 {
+        let s = input.as_bytes();
+        let mut enabled = true;
+        let mut total = 0;
+        for i in 0..s.len() {
+            let rest = &s[i..];
+            if rest.starts_with(b"do()") {
+                enabled = true;
+            } else if rest.starts_with(b"don't()") {
+                enabled = !obey;
+            } else if let Some(args) = rest.strip_prefix(b"mul(") {
+                let (a_len, a) = digits(args);
+                let after_a = &args[a_len..];
+                if a_len == 0 || after_a.first() != Some(&b',') {
+                    continue;
+                }
+                let (b_len, b) = digits(&after_a[1..]);
+                if b_len > 0 && after_a.get(1 + b_len) == Some(&b')') && enabled {
+                    total += a * b;
+                }
+            }
+        }
+        total
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(3, 100, 20) {
+            let program = parse(&input).unwrap();
+            assert_eq!(part1(&program), oracle(&input, false).into(), "seed {}", seed);
+            assert_eq!(part2(&program), oracle(&input, true).into(), "seed {}", seed);
+        }
+    }
 }
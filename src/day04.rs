@@ -1,124 +1,67 @@
-use std::convert::TryFrom;
-
-#[derive(Debug)]
-struct Grid {
-    chars: Vec<char>,
-    width: usize,
-    height: usize,
-}
-
-#[derive(Debug, Clone, Copy)]
-struct Point {
-    x: i32,
-    y: i32,
-}
-
-impl Grid {
-    fn new(input: &str) -> Option<Self> {
-        let chars = input.lines().flat_map(|line| line.chars()).collect();
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            chars,
-            width,
-            height,
-        })
-    }
-
-    fn get(&self, Point { x, y }: Point) -> Option<char> {
-        if self.is_valid_point(Point { x, y }) {
-            Some(self.chars[y as usize * self.width + x as usize])
-        } else {
-            None
-        }
-    }
-
-    fn is_valid_point(&self, Point { x, y }: Point) -> bool {
-        x >= 0 && x < self.width as i32 && y >= 0 && y < self.height as i32
-    }
-
-    fn points(&self) -> impl Iterator<Item = Point> + '_ {
-        (0..self.height as i32)
-            .flat_map(move |y| (0..self.width as i32).map(move |x| Point { x, y }))
-    }
-}
-
-struct WordSearch<'a> {
-    grid: &'a Grid,
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parser::{ParseError, Parser};
+use crate::point::{Point, ADJACENT};
+use crate::solution::Example;
+
+pub struct WordSearch<'a> {
+    pub grid: &'a Grid<char>,
 }
 
 impl<'a> WordSearch<'a> {
-    fn new(grid: &'a Grid) -> Self {
+    fn new(grid: &'a Grid<char>) -> Self {
         Self { grid }
     }
 
-    const DIRECTIONS: [(i32, i32); 8] = [
-        (0, 1),
-        (1, 0),
-        (1, 1),
-        (1, -1),
-        (0, -1),
-        (-1, 0),
-        (-1, -1),
-        (-1, 1),
-    ];
-
-    fn search_from(&self, start: Point, (dx, dy): (i32, i32), target: &[char]) -> bool {
-        target.iter().enumerate().all(|(i, &c)| {
-            let point = Point {
-                x: start.x + dx * i32::try_from(i).unwrap(),
-                y: start.y + dy * i32::try_from(i).unwrap(),
-            };
-            self.grid.get(point) == Some(c)
-        })
+    fn search_from(&self, start: Point, dir: Point, target: &[char]) -> bool {
+        target
+            .iter()
+            .zip(0..)
+            .all(|(c, i)| self.grid.get(start + dir * i) == Some(c))
     }
 
     fn count(&self, target: &[char]) -> usize {
         self.grid
-            .points()
-            .flat_map(|point| {
-                Self::DIRECTIONS
+            .positions()
+            .flat_map(|pos| {
+                ADJACENT
                     .iter()
-                    .filter(move |&&dir| self.search_from(point, dir, target))
+                    .filter(move |&&dir| self.search_from(pos, dir, target))
             })
             .count()
     }
 }
 
-struct XMasSearch<'a> {
-    grid: &'a Grid,
+pub struct XMasSearch<'a> {
+    pub grid: &'a Grid<char>,
 }
 
 impl<'a> XMasSearch<'a> {
-    fn new(grid: &'a Grid) -> Self {
+    fn new(grid: &'a Grid<char>) -> Self {
         Self { grid }
     }
 
-    const PATTERNS: [[(i32, i32); 3]; 4] = [
-        [(-1, -1), (0, 0), (1, 1)],
-        [(1, -1), (0, 0), (-1, 1)],
-        [(1, 1), (0, 0), (-1, -1)],
-        [(-1, 1), (0, 0), (1, -1)],
+    const PATTERNS: [[Point; 3]; 4] = [
+        [Point::new(-1, -1), Point::ORIGIN, Point::new(1, 1)],
+        [Point::new(1, -1), Point::ORIGIN, Point::new(-1, 1)],
+        [Point::new(1, 1), Point::ORIGIN, Point::new(-1, -1)],
+        [Point::new(-1, 1), Point::ORIGIN, Point::new(1, -1)],
     ];
 
     const TARGET: [char; 3] = ['M', 'A', 'S'];
 
     fn is_match(&self, center: Point) -> bool {
-        if self.grid.get(center) != Some('A') {
+        if self.grid.get(center) != Some(&'A') {
             return false;
         }
 
         let matching_patterns = Self::PATTERNS
             .iter()
             .filter(|pattern| {
-                pattern.iter().zip(&Self::TARGET).all(|(&(dx, dy), &c)| {
-                    let point = Point {
-                        x: center.x + dx,
-                        y: center.y + dy,
-                    };
-                    self.grid.get(point) == Some(c)
-                })
+                pattern
+                    .iter()
+                    .zip(&Self::TARGET)
+                    .all(|(&offset, c)| self.grid.get(center + offset) == Some(c))
             })
             .count();
 
@@ -127,20 +70,90 @@ impl<'a> XMasSearch<'a> {
 
     fn count(&self) -> usize {
         self.grid
-            .points()
-            .filter(|&point| self.is_match(point))
+            .positions()
+            .filter(|&pos| self.is_match(pos))
             .count()
     }
 }
 
-pub fn solution(input: &str) {
-    let grid = Grid::new(input).unwrap();
+pub fn parse(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::parse_chars(&Parser::new(input), input)
+}
+
+pub fn part1(grid: &Grid<char>) -> Answer {
+    let word_search = WordSearch::new(grid);
+    word_search.count(&['X', 'M', 'A', 'S']).into()
+}
 
-    let word_search = WordSearch::new(&grid);
-    let part1 = word_search.count(&['X', 'M', 'A', 'S']);
-    println!("Part 1: {}", part1);
+pub fn part2(grid: &Grid<char>) -> Answer {
+    let xmas_search = XMasSearch::new(grid);
+    xmas_search.count().into()
+}
 
-    let xmas_search = XMasSearch::new(&grid);
-    let part2 = xmas_search.count();
-    println!("Part 2: {}", part2);
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+",
+    params: &[],
+    part1: Some("18"),
+    part2: Some("9"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::gen;
+
+    // Read off every row, column and diagonal as a string and look for the word both ways
+    fn oracle1(grid: &Grid<char>) -> usize {
+        let mut lines: BTreeMap<(u8, isize), String> = BTreeMap::new();
+        for (pos, &c) in grid.iter() {
+            for key in [(0, pos.y), (1, pos.x), (2, pos.x - pos.y), (3, pos.x + pos.y)] {
+                lines.entry(key).or_default().push(c);
+            }
+        }
+        lines
+            .values()
+            .map(|line| line.matches("XMAS").count() + line.matches("SAMX").count())
+            .sum()
+    }
+
+    fn oracle2(grid: &Grid<char>) -> usize {
+        let mas = |a: Point, b: Point, c: Point| {
+            let word: String = [a, b, c].iter().map(|&pos| grid[pos]).collect();
+            word == "MAS" || word == "SAM"
+        };
+        let mut count = 0;
+        for y in 1..grid.height() as isize - 1 {
+            for x in 1..grid.width() as isize - 1 {
+                let centre = Point::new(x, y);
+                let (nw, ne) = (Point::new(x - 1, y - 1), Point::new(x + 1, y - 1));
+                let (sw, se) = (Point::new(x - 1, y + 1), Point::new(x + 1, y + 1));
+                if mas(nw, centre, se) && mas(ne, centre, sw) {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(4, 12, 20) {
+            let grid = parse(&input).unwrap();
+            assert_eq!(part1(&grid), oracle1(&grid).into(), "seed {}", seed);
+            assert_eq!(part2(&grid), oracle2(&grid).into(), "seed {}", seed);
+        }
+    }
 }
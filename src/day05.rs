@@ -1,54 +1,75 @@
 use std::collections::HashSet;
 
-pub fn solution(input: &str) {
-    let (rules, updates) = input.split_once("\n\n").unwrap();
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
 
-    let rules: HashSet<(u32, u32)> = rules
+pub struct Manual {
+    pub rules: HashSet<(u32, u32)>,
+    pub updates: Vec<Vec<u32>>,
+}
+
+impl Manual {
+    fn valid_update(&self, update: &[u32]) -> bool {
+        update.is_sorted_by(|&a, &b| self.rules.contains(&(a, b)))
+    }
+}
+
+pub fn parse(input: &str) -> Result<Manual, ParseError> {
+    let p = Parser::new(input);
+    let (rules, updates) = p.sections()?;
+
+    let rules = rules
         .lines()
         .map(|line| {
-            let mut parts = line.split('|').map(|part| part.parse::<u32>().unwrap());
-            let before = parts.next().unwrap();
-            let page = parts.next().unwrap();
-            (before, page)
+            let (before, page) = p.split_once(line, "|")?;
+            Ok((p.number(before)?, p.number(page)?))
         })
-        .collect();
-
-    let valid_update =
-        |update: &[u32]| -> bool { update.is_sorted_by(|&a, &b| rules.contains(&(a, b))) };
+        .collect::<Result<_, _>>()?;
 
-    // Reuse vec
-    let mut update = Vec::new();
-
-    let part1: u32 = updates
+    let updates = updates
         .lines()
-        .filter_map(|line| {
-            update.clear();
-            update.extend(line.split(',').map(|page| page.parse::<u32>().unwrap()));
+        .map(|line| p.list(line, ","))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Manual { rules, updates })
+}
 
+pub fn part1(manual: &Manual) -> Answer {
+    let part1: u32 = manual
+        .updates
+        .iter()
+        .filter_map(|update| {
             // If sorted, return middle element
-            if valid_update(&update) {
+            if manual.valid_update(update) {
                 Some(update[update.len() / 2])
             } else {
                 None
             }
         })
         .sum();
-    println!("Part 1: {}", part1);
+    part1.into()
+}
 
-    let part2: u32 = updates
-        .lines()
-        .flat_map(|line| {
-            update.clear();
-            update.extend(line.split(',').map(|page| page.parse::<u32>().unwrap()));
+pub fn part2(manual: &Manual) -> Answer {
+    // Reuse vec
+    let mut update = Vec::new();
 
+    let part2: u32 = manual
+        .updates
+        .iter()
+        .flat_map(|pages| {
             // Already valid, ignore
-            if valid_update(&update) {
+            if manual.valid_update(pages) {
                 return None;
             }
 
+            update.clear();
+            update.extend_from_slice(pages);
+
             let m = update.len() / 2;
             let (_, &mut m, _) = update.select_nth_unstable_by(m, |&a, &b| {
-                if rules.contains(&(a, b)) {
+                if manual.rules.contains(&(a, b)) {
                     std::cmp::Ordering::Less
                 } else {
                     std::cmp::Ordering::Equal
@@ -58,5 +79,97 @@ pub fn solution(input: &str) {
             Some(m)
         })
         .sum();
-    println!("Part 2: {}", part2);
+    part2.into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47
+",
+    params: &[],
+    part1: Some("143"),
+    part2: Some("123"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    // In order unless some later page has a rule saying it goes before an earlier one
+    fn in_order(manual: &Manual, update: &[u32]) -> bool {
+        (0..update.len()).all(|i| {
+            update[i + 1..]
+                .iter()
+                .all(|&later| !manual.rules.contains(&(later, update[i])))
+        })
+    }
+
+    fn oracle1(manual: &Manual) -> u32 {
+        manual
+            .updates
+            .iter()
+            .filter(|update| in_order(manual, update))
+            .map(|update| update[update.len() / 2])
+            .sum()
+    }
+
+    // Put each update in order in full, by taking a page nothing left must come before
+    fn oracle2(manual: &Manual) -> u32 {
+        manual
+            .updates
+            .iter()
+            .filter(|update| !in_order(manual, update))
+            .map(|update| {
+                let mut left = update.clone();
+                let mut sorted = Vec::new();
+                while !left.is_empty() {
+                    let i = (0..left.len())
+                        .find(|&i| {
+                            left.iter()
+                                .all(|&other| !manual.rules.contains(&(other, left[i])))
+                        })
+                        .unwrap();
+                    sorted.push(left.remove(i));
+                }
+                sorted[sorted.len() / 2]
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(5, 20, 20) {
+            let manual = parse(&input).unwrap();
+            assert_eq!(part1(&manual), oracle1(&manual).into(), "seed {}", seed);
+            assert_eq!(part2(&manual), oracle2(&manual).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,116 +1,91 @@
 use std::collections::HashSet;
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-
-struct Map {
-    tiles: Vec<char>,
-    width: usize,
-    height: usize,
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parser::{ParseError, Parser};
+use crate::point::{Direction, Point};
+use crate::render::{self, Canvas, Rgb};
+use crate::solution::Example;
+
+pub struct Map {
+    pub grid: Grid<char>,
+    /// Where the guard starts, facing up
+    pub start: Point,
 }
 
 impl Map {
-    fn new(input: &str) -> Option<Self> {
-        let tiles = input.lines().flat_map(|line| line.chars()).collect();
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            tiles,
-            width,
-            height,
-        })
-    }
-
-    fn get_tile(&self, (x, y): (isize, isize)) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.tiles[y * self.width + x])
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let p = Parser::new(input);
+        let grid = Grid::parse_chars(&p, input)?;
+        let start = grid
+            .find(&'^')
+            .ok_or_else(|| p.error(input, "a guard '^' in the map"))?;
+        Ok(Self { grid, start })
     }
 
-    fn find(&self, c: char) -> Option<(isize, isize)> {
-        self.tiles
-            .iter()
-            .enumerate()
-            .find_map(|(i, &tile)| {
-                if tile == c {
-                    Some((i % self.width, i / self.width))
-                } else {
-                    None
-                }
-            })
-            .map(|(x, y)| (x as isize, y as isize))
-    }
-
-    fn obstructed(
-        &self,
-        (x, y): (isize, isize),
-        (dx, dy): (isize, isize),
-        obstruction: Option<(isize, isize)>,
-    ) -> bool {
-        let next_pos = (x + dx, y + dy);
+    fn obstructed(&self, pos: Point, direction: Direction, obstruction: Option<Point>) -> bool {
+        let next_pos = pos + direction;
         if let Some(obstruction_pos) = obstruction {
             if next_pos == obstruction_pos {
                 return true;
             }
         }
-        matches!(self.get_tile(next_pos), Some('#'))
+        self.grid.get(next_pos) == Some(&'#')
     }
 
-    fn guard_path(&self, start: (isize, isize)) -> HashSet<(isize, isize)> {
+    /// Every position the guard visits, writing a frame for each step if `animate` is set
+    fn guard_path(&self, start: Point, animate: bool) -> HashSet<Point> {
         let mut visited = HashSet::new();
         let mut pos = start;
-        let mut direction = 0;
+        let mut direction = Direction::Up;
+        let mut canvas = animate.then(|| Canvas::from_chars(&self.grid));
 
-        while self.get_tile(pos).is_some() {
+        while self.grid.get(pos).is_some() {
             // Mark the current position as visited
             visited.insert(pos);
 
-            let (dx, dy) = DIRECTIONS[direction];
+            if let Some(canvas) = &mut canvas {
+                canvas.glyph(pos, direction.to_char());
+                canvas.colour(pos, Rgb::RED);
+                render::frame("guard", canvas);
+                canvas.glyph(pos, 'X');
+                canvas.colour(pos, Rgb::YELLOW);
+            }
 
-            if self.obstructed(pos, (dx, dy), None) {
-                // Turn right
-                direction = (direction + 1) % 4;
+            if self.obstructed(pos, direction, None) {
+                direction = direction.turn_right();
             } else {
                 // Step forward
-                pos = (pos.0 + dx, pos.1 + dy);
+                pos += direction;
             }
         }
 
         visited
     }
 
-    fn new_loop(&self, start: (isize, isize), obstruction: (isize, isize)) -> bool {
+    fn new_loop(&self, start: Point, obstruction: Point) -> bool {
         let mut visited = HashSet::new();
         let mut pos = start;
-        let mut direction = 0;
+        let mut direction = Direction::Up;
 
-        while self.get_tile(pos).is_some() {
+        while self.grid.get(pos).is_some() {
             // If we've visited this position and direction before, we're in a loop
             if !visited.insert((pos, direction)) {
                 return true;
             }
 
-            let (dx, dy) = DIRECTIONS[direction];
-
-            if self.obstructed(pos, (dx, dy), Some(obstruction)) {
-                // Turn right
-                direction = (direction + 1) % 4;
+            if self.obstructed(pos, direction, Some(obstruction)) {
+                direction = direction.turn_right();
             } else {
                 // Step forward
-                pos = (pos.0 + dx, pos.1 + dy);
+                pos += direction;
             }
         }
 
         false
     }
 
-    fn count_loops(&self, start: (isize, isize), visited: &HashSet<(isize, isize)>) -> usize {
+    fn count_loops(&self, start: Point, visited: &HashSet<Point>) -> usize {
         visited
             .iter()
             .filter(|&&pos| pos != start && self.new_loop(start, pos))
@@ -118,14 +93,93 @@ impl Map {
     }
 }
 
-pub fn solution(input: &str) {
-    let map = Map::new(input).unwrap();
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Map::new(input)
+}
+
+pub fn part1(map: &Map) -> Answer {
+    map.guard_path(map.start, render::frames_enabled())
+        .len()
+        .into()
+}
+
+pub fn part2(map: &Map) -> Answer {
+    let path = map.guard_path(map.start, false);
 
-    // Find the start square denoted by '^'
-    let start = map.find('^').unwrap();
+    map.count_loops(map.start, &path).into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...
+",
+    params: &[],
+    part1: Some("41"),
+    part2: Some("6"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::gen;
+
+    #[test]
+    fn test_missing_guard() {
+        let err = parse("...\n.#.\n...\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.expected, "a guard '^' in the map");
+    }
 
-    let path = map.guard_path(start);
+    /// Where the guard goes with `grid` as it is, or `None` if she never leaves.
+    fn walk(grid: &Grid<char>) -> Option<HashSet<Point>> {
+        let mut pos = grid.find(&'^').unwrap();
+        let mut direction = Direction::Up;
+        let mut seen = HashSet::new();
+        loop {
+            if !seen.insert((pos, direction)) {
+                return None;
+            }
+            match grid.get(pos + direction) {
+                None => return Some(seen.into_iter().map(|(pos, _)| pos).collect()),
+                Some('#') => direction = direction.turn_right(),
+                Some(_) => pos += direction,
+            }
+        }
+    }
 
-    println!("Part 1: {}", path.len());
-    println!("Part 2: {}", map.count_loops(start, &path));
+    fn oracle1(map: &Map) -> usize {
+        walk(&map.grid).unwrap().len()
+    }
+
+    // Try an obstruction on every open square, not just those on the guard's path
+    fn oracle2(map: &Map) -> usize {
+        map.grid
+            .find_all(&'.')
+            .filter(|&pos| {
+                let mut grid = map.grid.clone();
+                grid[pos] = '#';
+                walk(&grid).is_none()
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(6, 12, 20) {
+            let map = parse(&input).unwrap();
+            assert_eq!(part1(&map), oracle1(&map).into(), "seed {}", seed);
+            assert_eq!(part2(&map), oracle2(&map).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,9 +1,9 @@
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 fn concat(a: u64, b: u64) -> u64 {
-    let digits = if b == 0 {
-        1
-    } else {
-        (b as f64).log10().floor() as u32 + 1
-    };
+    let digits = b.checked_ilog10().unwrap_or(0) + 1;
     a * 10_u64.pow(digits) + b
 }
 
@@ -40,38 +40,105 @@ fn valid_equation(target: u64, result: u64, numbers: &[u64], cat: bool) -> bool
     false
 }
 
-pub fn solution(input: &str) {
-    let mut numbers = Vec::new();
+pub struct Equations {
+    pub equations: Vec<(u64, Vec<u64>)>,
+}
 
-    let (part1, part2) = input
+pub fn parse(input: &str) -> Result<Equations, ParseError> {
+    let p = Parser::new(input);
+    let equations = p
         .lines()
         .map(|line| {
-            let (target, num_str) = line.split_once(':').unwrap();
+            let (target, num_str) = p.split_once(line, ":")?;
 
             // Parse the target and numbers
-            let target = target.parse::<u64>().unwrap();
-            numbers.clear();
-            numbers.extend(
-                num_str
-                    .split_whitespace()
-                    .map(|n| n.parse::<u64>().unwrap()),
-            );
-
-            let part1 = if valid_equation(target, 0, &numbers, false) {
-                target
-            } else {
-                0
-            };
-            let part2 = if valid_equation(target, 0, &numbers, true) {
-                target
-            } else {
-                0
-            };
-
-            (part1, part2)
+            let target = p.number::<u64>(target)?;
+            let numbers = p.numbers::<u64>(num_str)?;
+            if numbers.is_empty() {
+                return Err(p.error_after(line, "at least one number"));
+            }
+
+            Ok((target, numbers))
         })
-        .fold((0, 0), |(acc1, acc2), (p1, p2)| (acc1 + p1, acc2 + p2));
+        .collect::<Result<_, _>>()?;
+
+    Ok(Equations { equations })
+}
+
+fn calibration_result(equations: &Equations, cat: bool) -> u64 {
+    equations
+        .equations
+        .iter()
+        // Start from the first number, as starting from 0 would let it be multiplied away
+        .filter(|(target, numbers)| valid_equation(*target, numbers[0], &numbers[1..], cat))
+        .map(|(target, _)| target)
+        .sum()
+}
+
+pub fn part1(equations: &Equations) -> Answer {
+    calibration_result(equations, false).into()
+}
 
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+pub fn part2(equations: &Equations) -> Answer {
+    calibration_result(equations, true).into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20
+",
+    params: &[],
+    part1: Some("3749"),
+    part2: Some("11387"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    // Try every combination of operators, counting them off in base 2 or 3
+    fn oracle(equations: &Equations, operators: u64) -> u64 {
+        equations
+            .equations
+            .iter()
+            .filter(|(target, numbers)| {
+                let gaps = numbers.len() as u32 - 1;
+                (0..operators.pow(gaps)).any(|mut combination| {
+                    // Overflowing is never the target, since every operator only grows it
+                    let mut result = Some(numbers[0]);
+                    for &n in &numbers[1..] {
+                        result = result.and_then(|result| match combination % operators {
+                            0 => result.checked_add(n),
+                            1 => result.checked_mul(n),
+                            _ => {
+                                let digits = n.to_string().len() as u32;
+                                result.checked_mul(10u64.pow(digits))?.checked_add(n)
+                            }
+                        });
+                        combination /= operators;
+                    }
+                    result == Some(*target)
+                })
+            })
+            .map(|(target, _)| target)
+            .sum()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(7, 20, 5) {
+            let equations = parse(&input).unwrap();
+            assert_eq!(part1(&equations), oracle(&equations, 2).into(), "seed {}", seed);
+            assert_eq!(part2(&equations), oracle(&equations, 3).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,6 +1,68 @@
 use std::collections::HashSet;
 
-pub fn solution(input: &str) {
+use crate::answer::Answer;
+use crate::log::{self, debug, Level};
+use crate::parser::{ParseError, Parser};
+use crate::point::Point;
+use crate::render::{Canvas, Rgb};
+use crate::solution::Example;
+
+pub struct City<'a> {
+    pub rows: Vec<&'a str>,
+    pub antennas: Vec<(char, Point)>,
+    pub width: isize,
+    pub height: isize,
+}
+
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl City<'_> {
+    /// Every square in line with a pair of antennas, tagged with whether it's a first order
+    /// antinode: beyond one of the pair by the distance between them
+    fn antinodes(&self) -> HashSet<(bool, Point)> {
+        let mut antinodes = HashSet::new();
+
+        // For every unique pair of antennas
+        for (i, &(a_freq, a)) in self.antennas.iter().enumerate() {
+            for &(b_freq, b) in self.antennas.iter().skip(i + 1) {
+                // Must be same "frequency"
+                if a_freq != b_freq {
+                    continue;
+                }
+
+                // Step to each square on the line, which can be closer together than the
+                // antennas when the offset between them has a common factor
+                let gap = b - a;
+                let factor = gcd(gap.x, gap.y);
+                let step = Point::new(gap.x / factor, gap.y / factor);
+
+                let gen_antinodes = |step: Point| {
+                    (0..)
+                        .map(move |i| (i, a + step * i))
+                        .take_while(|&(_, Point { x, y })| {
+                            x >= 0 && y >= 0 && x < self.width && y < self.height
+                        })
+                };
+
+                // Beyond b going forwards, and beyond a going backwards
+                antinodes.extend(gen_antinodes(step).map(|(i, pos)| (i == 2 * factor, pos)));
+                antinodes.extend(gen_antinodes(-step).map(|(i, pos)| (i == factor, pos)));
+            }
+        }
+
+        antinodes
+    }
+}
+
+pub fn parse(input: &str) -> Result<City<'_>, ParseError> {
+    let (width, height) = Parser::new(input).grid_size(input)?;
+
     // Collect all antenna locations
     let antennas: Vec<_> = input
         .lines()
@@ -8,64 +70,123 @@ pub fn solution(input: &str) {
         .flat_map(|(y, line)| {
             line.chars().enumerate().filter_map(move |(x, char)| {
                 if char != '.' {
-                    Some((char, (x as isize, y as isize)))
+                    Some((char, Point::new(x as isize, y as isize)))
                 } else {
                     None
                 }
             })
         })
         .collect();
-    let width = input.lines().next().unwrap().len() as isize;
-    let height = input.lines().count() as isize;
-
-    let mut antinodes = HashSet::new();
 
-    // For every unique pair of antennas
-    for (i, &(a_freq, (ax, ay))) in antennas.iter().enumerate() {
-        for &(b_freq, (bx, by)) in antennas.iter().skip(i + 1) {
-            // Must be same "frequency"
-            if a_freq != b_freq {
-                continue;
-            }
-
-            // Calculate the step size
-            let dx = bx - ax;
-            let dy = by - ay;
-
-            let gen_antinodes = |start_x, start_y, dx, dy| {
-                (0..)
-                    .map(move |i| {
-                        let nx = start_x + dx * i;
-                        let ny = start_y + dy * i;
-                        (i, (nx, ny))
-                    })
-                    .take_while(|&(_, (nx, ny))| nx >= 0 && ny >= 0 && nx < width && ny < height)
-            };
-
-            antinodes.extend(gen_antinodes(bx, by, dx, dy));
-            antinodes.extend(gen_antinodes(ax, ay, -dx, -dy));
-        }
-    }
+    Ok(City {
+        rows: input.lines().collect(),
+        antennas,
+        width: width as isize,
+        height: height as isize,
+    })
+}
 
-    let first_order: HashSet<_> = antinodes
+pub fn part1(city: &City) -> Answer {
+    let first_order: HashSet<_> = city
+        .antinodes()
         .iter()
-        .filter(|&&(order, _)| order == 1)
+        .filter(|&&(first, _)| first)
         .map(|&(_, pos)| pos)
         .collect();
-    println!("Part 1: {}", first_order.len());
+    first_order.len().into()
+}
 
-    let all_orders: HashSet<_> = antinodes.iter().map(|&(_, pos)| pos).collect();
-    println!("Part 2: {}", all_orders.len());
+pub fn part2(city: &City) -> Answer {
+    let all_orders: HashSet<_> = city.antinodes().iter().map(|&(_, pos)| pos).collect();
 
     // Print map with antinodes
-    input.lines().enumerate().for_each(|(y, line)| {
-        line.chars().enumerate().for_each(|(x, char)| {
-            if all_orders.contains(&(x as isize, y as isize)) {
-                print!("#");
-            } else {
-                print!("{}", char);
-            }
-        });
-        println!();
-    });
+    if log::enabled(Level::Debug) {
+        let mut map = Canvas::from_text(&city.rows.join("\n"));
+        for &(_, pos) in &city.antennas {
+            map.colour(pos, Rgb::BLUE);
+        }
+        for &pos in &all_orders {
+            map.glyph(pos, '#');
+            map.colour(pos, Rgb::RED);
+        }
+        debug!("{}", map.for_stderr());
+    }
+
+    all_orders.len().into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+............
+........0...
+.....0......
+.......0....
+....0.......
+......A.....
+............
+............
+........A...
+.........A..
+............
+............
+",
+    params: &[],
+    part1: Some("14"),
+    part2: Some("34"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    /// Every pair of distinct antennas sharing a frequency, each way round.
+    fn pairs<'a>(city: &'a City) -> impl Iterator<Item = (Point, Point)> + 'a {
+        city.antennas.iter().flat_map(move |&(a_freq, a)| {
+            city.antennas
+                .iter()
+                .filter(move |&&(b_freq, b)| b_freq == a_freq && b != a)
+                .map(move |&(_, b)| (a, b))
+        })
+    }
+
+    fn cross(a: Point, b: Point) -> isize {
+        a.x * b.y - a.y * b.x
+    }
+
+    fn squared_length(a: Point) -> isize {
+        a.x * a.x + a.y * a.y
+    }
+
+    /// Test every square of the map against every pair of antennas.
+    fn oracle(city: &City, is_antinode: impl Fn(Point, Point, Point) -> bool) -> usize {
+        (0..city.height)
+            .flat_map(|y| (0..city.width).map(move |x| Point::new(x, y)))
+            .filter(|&pos| pairs(city).any(|(a, b)| is_antinode(pos, a, b)))
+            .count()
+    }
+
+    // In line with the pair, beyond it on b's side, and twice as far from a as from b
+    fn oracle1(city: &City) -> usize {
+        oracle(city, |pos, a, b| {
+            let (to_a, to_b) = (a - pos, b - pos);
+            cross(to_a, to_b) == 0
+                && to_a.x * to_b.x + to_a.y * to_b.y > 0
+                && squared_length(to_a) == 4 * squared_length(to_b)
+        })
+    }
+
+    // Anywhere in line with the pair, including between them
+    fn oracle2(city: &City) -> usize {
+        oracle(city, |pos, a, b| cross(a - pos, b - pos) == 0)
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(8, 12, 20) {
+            let city = parse(&input).unwrap();
+            assert_eq!(part1(&city), oracle1(&city).into(), "seed {}", seed);
+            assert_eq!(part2(&city), oracle2(&city).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,3 +1,11 @@
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
+pub struct Disk {
+    pub blocks: Vec<Option<u64>>,
+}
+
 fn checksum(fs: &[Option<u64>]) -> u64 {
     fs.iter()
         .enumerate()
@@ -5,7 +13,30 @@ fn checksum(fs: &[Option<u64>]) -> u64 {
         .sum()
 }
 
-fn part1(mut fs: Vec<Option<u64>>) {
+pub fn parse(input: &str) -> Result<Disk, ParseError> {
+    let p = Parser::new(input);
+    let map = input.trim();
+
+    let mut blocks = Vec::new();
+    for (i, (offset, c)) in map.char_indices().enumerate() {
+        let digit = c
+            .to_digit(10)
+            .ok_or_else(|| p.error(&map[offset..offset + c.len_utf8()], "a digit"))?;
+        let is_space = i % 2 == 1;
+        let id = (i / 2) as u64;
+
+        blocks.extend(std::iter::repeat_n(
+            if is_space { None } else { Some(id) },
+            digit as usize,
+        ));
+    }
+
+    Ok(Disk { blocks })
+}
+
+pub fn part1(disk: &Disk) -> Answer {
+    let mut fs = disk.blocks.clone();
+
     let mut left = 0;
     let mut right = fs.len();
 
@@ -25,10 +56,12 @@ fn part1(mut fs: Vec<Option<u64>>) {
         left += 1;
     }
 
-    println!("Part 1: {}", checksum(&fs));
+    checksum(&fs).into()
 }
 
-fn part2(mut fs: Vec<Option<u64>>) {
+pub fn part2(disk: &Disk) -> Answer {
+    let mut fs = disk.blocks.clone();
+
     // Get unique file IDs in descending order
     let file_ids: Vec<_> = fs
         .iter()
@@ -65,23 +98,73 @@ fn part2(mut fs: Vec<Option<u64>>) {
         }
     }
 
-    println!("Part 2: {}", checksum(&fs));
+    checksum(&fs).into()
 }
 
-pub fn solution(input: &str) {
-    let fs: Vec<_> = input
-        .trim()
-        .chars()
-        .enumerate()
-        .flat_map(|(i, c)| {
-            let digit = c.to_digit(10).expect("Input should be digits");
-            let is_space = i % 2 == 1;
-            let id = (i / 2) as u64;
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+2333133121414131402
+",
+    params: &[],
+    part1: Some("1928"),
+    part2: Some("2858"),
+}];
 
-            std::iter::repeat(if is_space { None } else { Some(id) }).take(digit as usize)
-        })
-        .collect();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    /// The disk block by block, read straight from the input.
+    fn blocks(input: &str) -> Vec<Option<u64>> {
+        input
+            .trim()
+            .chars()
+            .enumerate()
+            .flat_map(|(i, c)| {
+                let id = (i % 2 == 0).then_some(i as u64 / 2);
+                vec![id; c.to_digit(10).unwrap() as usize]
+            })
+            .collect()
+    }
+
+    // Swap the first gap with the last block until the gaps are all at the end
+    fn oracle1(input: &str) -> u64 {
+        let mut disk = blocks(input);
+        loop {
+            let gap = disk.iter().position(Option::is_none);
+            let last = disk.iter().rposition(Option::is_some);
+            match (gap, last) {
+                (Some(gap), Some(last)) if gap < last => disk.swap(gap, last),
+                _ => return checksum(&disk),
+            }
+        }
+    }
 
-    part1(fs.clone());
-    part2(fs);
+    // Move each file once, highest ID first, to the first gap before it that fits it
+    fn oracle2(input: &str) -> u64 {
+        let mut disk = blocks(input);
+        let highest = disk.iter().flatten().max().copied().unwrap_or(0);
+        for id in (0..=highest).rev() {
+            let start = disk.iter().position(|&b| b == Some(id)).unwrap();
+            let len = disk.iter().filter(|&&b| b == Some(id)).count();
+            if let Some(to) =
+                (0..start).find(|&to| disk[to..to + len].iter().all(Option::is_none))
+            {
+                for i in 0..len {
+                    disk.swap(to + i, start + i);
+                }
+            }
+        }
+        checksum(&disk)
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(9, 30, 20) {
+            let disk = parse(&input).unwrap();
+            assert_eq!(part1(&disk), oracle1(&input).into(), "seed {}", seed);
+            assert_eq!(part2(&disk), oracle2(&input).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,63 +1,28 @@
 use std::collections::HashSet;
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parser::{ParseError, Parser};
+use crate::point::Point;
+use crate::solution::Example;
 
-struct Map {
-    tiles: Vec<u32>,
-    width: usize,
-    height: usize,
+pub struct Map {
+    pub grid: Grid<u32>,
 }
 
 impl Map {
-    fn new(input: &str) -> Option<Self> {
-        let tiles = input
-            .lines()
-            .flat_map(|line| line.chars())
-            .map(|c| c.to_digit(10))
-            .collect::<Option<Vec<_>>>()?;
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            tiles,
-            width,
-            height,
-        })
-    }
-
-    fn get_tile(&self, (x, y): (isize, isize)) -> Option<u32> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.tiles[y * self.width + x])
-    }
-
-    fn find(&self, height: u32) -> impl Iterator<Item = (isize, isize)> + '_ {
-        self.tiles
-            .iter()
-            .enumerate()
-            .filter_map(move |(i, &tile)| {
-                if tile == height {
-                    Some((i % self.width, i / self.width))
-                } else {
-                    None
-                }
-            })
-            .map(|(x, y)| (x as isize, y as isize))
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse(&Parser::new(input), input, "a digit", |c| c.to_digit(10))?;
+        Ok(Self { grid })
     }
 
     fn count_trail_peaks(
         &self,
-        pos: (isize, isize),
+        pos: Point,
         target: u32,
-        visited: &mut HashSet<(isize, isize)>,
+        visited: &mut HashSet<Point>,
     ) -> usize {
-        match self.get_tile(pos) {
+        match self.grid.get(pos).copied() {
             // If out of bounds
             None => 0,
             // If height is not the target
@@ -65,18 +30,16 @@ impl Map {
             // If we've reached the end of the trail
             Some(9) => usize::from(visited.insert(pos)),
             // Count peaks in all directions
-            Some(_) => DIRECTIONS
-                .iter()
-                .map(|&(dx, dy)| {
-                    let next_pos = (pos.0 + dx, pos.1 + dy);
-                    self.count_trail_peaks(next_pos, target + 1, visited)
-                })
+            Some(_) => self
+                .grid
+                .neighbours4(pos)
+                .map(|next_pos| self.count_trail_peaks(next_pos, target + 1, visited))
                 .sum(),
         }
     }
 
-    fn count_unique_trails(&self, pos: (isize, isize), target: u32) -> usize {
-        match self.get_tile(pos) {
+    fn count_unique_trails(&self, pos: Point, target: u32) -> usize {
+        match self.grid.get(pos).copied() {
             // If out of bounds
             None => 0,
             // If height is not the target
@@ -84,32 +47,101 @@ impl Map {
             // If we've reached the end of the trail
             Some(9) => 1,
             // Count trails in all directions
-            Some(_) => DIRECTIONS
-                .iter()
-                .map(|&(dx, dy)| {
-                    let next_pos = (pos.0 + dx, pos.1 + dy);
-                    self.count_unique_trails(next_pos, target + 1)
-                })
+            Some(_) => self
+                .grid
+                .neighbours4(pos)
+                .map(|next_pos| self.count_unique_trails(next_pos, target + 1))
                 .sum(),
         }
     }
 }
 
-pub fn solution(input: &str) {
-    let map = Map::new(input).unwrap();
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Map::new(input)
+}
 
+pub fn part1(map: &Map) -> Answer {
     let part1 = map
-        .find(0)
+        .grid
+        .find_all(&0)
         .map(|start| {
             let mut visited = HashSet::new();
             map.count_trail_peaks(start, 0, &mut visited)
         })
         .sum::<usize>();
-    println!("Part 1: {}", part1);
+    part1.into()
+}
 
+pub fn part2(map: &Map) -> Answer {
     let part2 = map
-        .find(0)
+        .grid
+        .find_all(&0)
         .map(|start| map.count_unique_trails(start, 0))
         .sum::<usize>();
-    println!("Part 2: {}", part2);
+    part2.into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+",
+    params: &[],
+    part1: Some("36"),
+    part2: Some("81"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+
+    use super::*;
+    use crate::gen;
+
+    // Work down from the peaks a height at a time, so each square knows every peak it leads to
+    // and how many ways it has of getting to them
+    fn oracle(map: &Map) -> (usize, usize) {
+        let mut peaks: HashMap<Point, HashSet<Point>> = HashMap::new();
+        let mut ways: HashMap<Point, usize> = HashMap::new();
+        for height in (0..=9).rev() {
+            for pos in map.grid.find_all(&height) {
+                if height == 9 {
+                    peaks.insert(pos, HashSet::from([pos]));
+                    ways.insert(pos, 1);
+                    continue;
+                }
+
+                let uphill: Vec<Point> = map
+                    .grid
+                    .neighbours4(pos)
+                    .filter(|&next| map.grid[next] == height + 1)
+                    .collect();
+                let reached = uphill.iter().flat_map(|next| peaks[next].clone()).collect();
+                peaks.insert(pos, reached);
+                ways.insert(pos, uphill.iter().map(|next| ways[next]).sum());
+            }
+        }
+
+        let trailheads: Vec<Point> = map.grid.find_all(&0).collect();
+        (
+            trailheads.iter().map(|pos| peaks[pos].len()).sum(),
+            trailheads.iter().map(|pos| ways[pos]).sum(),
+        )
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(10, 20, 20) {
+            let map = parse(&input).unwrap();
+            let (score, rating) = oracle(&map);
+            assert_eq!(part1(&map), score.into(), "seed {}", seed);
+            assert_eq!(part2(&map), rating.into(), "seed {}", seed);
+        }
+    }
 }
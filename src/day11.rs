@@ -1,10 +1,13 @@
 use std::collections::HashMap;
 
+use crate::answer::Answer;
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 pub fn count_digits(n: u64) -> u32 {
-    if n == 0 {
-        return 1;
-    }
-    ((n as f64).log10() as u32) + 1
+    // Integer log, as a float one rounds numbers like 999999999999999 up to the next power
+    n.checked_ilog10().unwrap_or(0) + 1
 }
 
 fn split_stone(stone: u64) -> (u64, u64) {
@@ -13,7 +16,7 @@ fn split_stone(stone: u64) -> (u64, u64) {
     (stone / divisor, stone % divisor)
 }
 
-fn solve(stones: &mut [u64], steps: u32) -> usize {
+fn solve(stones: &[u64], steps: u32) -> usize {
     fn recursion(
         stone: u64,
         depth: u32,
@@ -30,7 +33,7 @@ fn solve(stones: &mut [u64], steps: u32) -> usize {
 
         let result = if stone == 0 {
             recursion(1, depth + 1, target, memo)
-        } else if count_digits(stone) % 2 == 0 {
+        } else if count_digits(stone).is_multiple_of(2) {
             let (a, b) = split_stone(stone);
             recursion(a, depth + 1, target, memo) + recursion(b, depth + 1, target, memo)
         } else {
@@ -48,28 +51,86 @@ fn solve(stones: &mut [u64], steps: u32) -> usize {
         .sum()
 }
 
-pub fn solution(input: &str) {
-    let stones: Vec<_> = input
-        .split_whitespace()
-        .map(|stone| stone.parse::<u64>().unwrap())
-        .collect();
+params! {
+    /// How many times to blink in each part
+    part1_blinks: u32 = 25,
+    part2_blinks: u32 = 75,
+}
+
+pub struct Stones {
+    pub stones: Vec<u64>,
+}
 
-    let part1 = solve(&mut stones.clone(), 25);
-    println!("Part 1: {}", part1);
+pub fn parse(input: &str) -> Result<Stones, ParseError> {
+    let stones = Parser::new(input).numbers(input)?;
+
+    Ok(Stones { stones })
+}
+
+pub fn part1(stones: &Stones, params: &Params) -> Answer {
+    solve(&stones.stones, params.part1_blinks).into()
+}
 
-    let part2 = solve(&mut stones.clone(), 75);
-    println!("Part 2: {}", part2);
+pub fn part2(stones: &Stones, params: &Params) -> Answer {
+    solve(&stones.stones, params.part2_blinks).into()
 }
 
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+125 17
+",
+    params: &[],
+    part1: Some("55312"),
+    part2: Some("65601038650482"),
+}];
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen;
 
     #[test]
     fn test_count_digits() {
         assert_eq!(count_digits(123), 3);
         assert_eq!(count_digits(1234), 4);
         assert_eq!(count_digits(12345), 5);
+        assert_eq!(count_digits(0), 1);
+        assert_eq!(count_digits(999_999_999_999_999), 15);
+    }
+
+    // Blink one stone at a time, keeping every stone in a list
+    fn oracle(stones: &Stones, blinks: u32) -> usize {
+        let mut stones = stones.stones.clone();
+        for _ in 0..blinks {
+            stones = stones
+                .into_iter()
+                .flat_map(|stone| {
+                    let digits = stone.to_string();
+                    if stone == 0 {
+                        vec![1]
+                    } else if digits.len() % 2 == 0 {
+                        let (left, right) = digits.split_at(digits.len() / 2);
+                        vec![left.parse().unwrap(), right.parse().unwrap()]
+                    } else {
+                        vec![stone * 2024]
+                    }
+                })
+                .collect();
+        }
+        stones.len()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        let params = Params {
+            part1_blinks: 10,
+            part2_blinks: 20,
+        };
+        for (seed, input) in gen::samples(11, 4, 20) {
+            let stones = parse(&input).unwrap();
+            assert_eq!(part1(&stones, &params), oracle(&stones, 10).into(), "seed {}", seed);
+            assert_eq!(part2(&stones, &params), oracle(&stones, 20).into(), "seed {}", seed);
+        }
     }
 
     #[test]
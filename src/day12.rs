@@ -1,63 +1,38 @@
 use std::collections::HashSet;
 
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-const CORNERS: [((isize, isize), (isize, isize)); 4] = [
-    ((0, -1), (1, 0)),
-    ((1, 0), (0, 1)),
-    ((0, 1), (-1, 0)),
-    ((-1, 0), (0, -1)),
-];
-
-struct Map {
-    tiles: Vec<char>,
-    width: usize,
-    height: usize,
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parser::{ParseError, Parser};
+use crate::point::{Direction, Point};
+use crate::solution::Example;
+
+pub struct Map {
+    pub grid: Grid<char>,
 }
 
 impl Map {
-    fn new(input: &str) -> Option<Self> {
-        let tiles = input
-            .lines()
-            .flat_map(|line| line.chars())
-            .collect::<Vec<_>>();
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            tiles,
-            width,
-            height,
-        })
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let grid = Grid::parse_chars(&Parser::new(input), input)?;
+        Ok(Self { grid })
     }
 
-    fn get_tile(&self, (x, y): (isize, isize)) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.tiles[y * self.width + x])
-    }
-
-    fn count_edges(&self, pos: (isize, isize)) -> usize {
-        let centre = self.get_tile(pos);
-        DIRECTIONS
-            .iter()
-            .map(|&(dx, dy)| (self.get_tile((pos.0 + dx, pos.1 + dy)) != centre) as usize)
+    fn count_edges(&self, pos: Point) -> usize {
+        let centre = self.grid.get(pos);
+        pos.neighbours4()
+            .map(|next| (self.grid.get(next) != centre) as usize)
             .sum::<usize>()
     }
 
-    fn count_corners(&self, pos: (isize, isize)) -> usize {
-        let centre = self.get_tile(pos);
-        CORNERS
+    fn count_corners(&self, pos: Point) -> usize {
+        let centre = self.grid.get(pos);
+        // Each corner lies between a direction and the one clockwise from it
+        Direction::ALL
             .iter()
-            .map(|&(d1, d2)| {
-                let d1_tile = self.get_tile((pos.0 + d1.0, pos.1 + d1.1));
-                let d2_tile = self.get_tile((pos.0 + d2.0, pos.1 + d2.1));
-                let diag_tile = self.get_tile((pos.0 + d1.0 + d2.0, pos.1 + d1.1 + d2.1));
+            .map(|&d1| {
+                let d2 = d1.turn_right();
+                let d1_tile = self.grid.get(pos + d1);
+                let d2_tile = self.grid.get(pos + d2);
+                let diag_tile = self.grid.get(pos + d1 + d2);
 
                 (d1_tile != centre && d2_tile != centre) as usize
                     + (d1_tile == centre && d2_tile == centre && diag_tile != centre) as usize
@@ -67,8 +42,8 @@ impl Map {
 
     fn explore_area(
         &self,
-        start: (isize, isize),
-        visited: &mut HashSet<(isize, isize)>,
+        start: Point,
+        visited: &mut HashSet<Point>,
     ) -> Option<(usize, usize, usize)> {
         // If we've already visited this tile, return
         if !visited.insert(start) {
@@ -76,7 +51,7 @@ impl Map {
         }
 
         // The kind of tile we're looking for
-        let target_char = self.get_tile(start)?;
+        let target_char = self.grid.get(start)?;
 
         let mut queue = vec![start];
         let mut perimeter = 0;
@@ -88,9 +63,8 @@ impl Map {
             corners += self.count_corners(pos);
             count += 1;
 
-            for &(dx, dy) in &DIRECTIONS {
-                let new_pos = (pos.0 + dx, pos.1 + dy);
-                if self.get_tile(new_pos) == Some(target_char) && visited.insert(new_pos) {
+            for new_pos in pos.neighbours4() {
+                if self.grid.get(new_pos) == Some(target_char) && visited.insert(new_pos) {
                     queue.push(new_pos);
                 }
             }
@@ -100,33 +74,136 @@ impl Map {
     }
 }
 
-pub fn solution(input: &str) {
-    let map = Map::new(input).unwrap();
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Map::new(input)
+}
 
+/// Price of fencing every region, given a region's area and its perimeter or corner count
+fn fence_price(map: &Map, price: impl Fn(usize, usize, usize) -> usize) -> usize {
     let mut visited = HashSet::new();
-    let (part1, part2) = (0..map.height)
-        .flat_map(|y| (0..map.width).map(move |x| (x as isize, y as isize)))
+    map.grid
+        .positions()
         .filter_map(|pos| map.explore_area(pos, &mut visited))
-        .fold((0, 0), |(part1, part2), (c, p, cr)| {
-            (part1 + c * p, part2 + c * cr)
-        });
+        .map(|(c, p, cr)| price(c, p, cr))
+        .sum()
+}
+
+pub fn part1(map: &Map) -> Answer {
+    fence_price(map, |count, perimeter, _| count * perimeter).into()
+}
 
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+pub fn part2(map: &Map) -> Answer {
+    fence_price(map, |count, _, corners| count * corners).into()
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+AAAA
+BBCD
+BBCC
+EEEC
+",
+        params: &[],
+        part1: Some("140"),
+        part2: Some("80"),
+    },
+    Example {
+        input: "\
+RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE
+",
+        params: &[],
+        part1: Some("1930"),
+        part2: Some("1206"),
+    },
+];
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gen;
+
+    /// Every region as its set of squares.
+    fn regions(map: &Map) -> Vec<HashSet<Point>> {
+        let mut seen = HashSet::new();
+        let mut regions = Vec::new();
+        for start in map.grid.positions() {
+            if seen.contains(&start) {
+                continue;
+            }
+            let plant = map.grid[start];
+            let mut region = HashSet::from([start]);
+            let mut queue = vec![start];
+            while let Some(pos) = queue.pop() {
+                for next in map.grid.neighbours4(pos) {
+                    if map.grid[next] == plant && region.insert(next) {
+                        queue.push(next);
+                    }
+                }
+            }
+            seen.extend(region.iter().copied());
+            regions.push(region);
+        }
+        regions
+    }
+
+    /// Each stretch of fence, as the square inside it and the way it faces out.
+    fn fences(region: &HashSet<Point>) -> HashSet<(Point, Direction)> {
+        region
+            .iter()
+            .flat_map(|&pos| Direction::ALL.map(|d| (pos, d)))
+            .filter(|&(pos, d)| !region.contains(&(pos + d)))
+            .collect()
+    }
+
+    fn oracle1(map: &Map) -> usize {
+        regions(map)
+            .iter()
+            .map(|region| region.len() * fences(region).len())
+            .sum()
+    }
+
+    // A side starts at each stretch of fence without another facing the same way to its right
+    fn oracle2(map: &Map) -> usize {
+        regions(map)
+            .iter()
+            .map(|region| {
+                let fences = fences(region);
+                let sides = fences
+                    .iter()
+                    .filter(|&&(pos, d)| !fences.contains(&(pos + d.turn_right(), d)))
+                    .count();
+                region.len() * sides
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(12, 15, 20) {
+            let map = parse(&input).unwrap();
+            assert_eq!(part1(&map), oracle1(&map).into(), "seed {}", seed);
+            assert_eq!(part2(&map), oracle2(&map).into(), "seed {}", seed);
+        }
+    }
 
     #[test]
     fn test_count_edges() {
         let input = "###\n#.#\n###";
         let map = Map::new(input).unwrap();
 
-        assert_eq!(map.count_edges((0, 0)), 2);
-        assert_eq!(map.count_edges((1, 0)), 2);
-        assert_eq!(map.count_edges((1, 1)), 4);
+        assert_eq!(map.count_edges(Point::new(0, 0)), 2);
+        assert_eq!(map.count_edges(Point::new(1, 0)), 2);
+        assert_eq!(map.count_edges(Point::new(1, 1)), 4);
     }
 
     #[test]
@@ -134,9 +211,9 @@ mod tests {
         let input = "###\n#.#\n###";
         let map = Map::new(input).unwrap();
 
-        assert_eq!(map.count_corners((0, 0)), 2);
-        assert_eq!(map.count_corners((1, 0)), 0);
-        assert_eq!(map.count_corners((1, 1)), 4);
+        assert_eq!(map.count_corners(Point::new(0, 0)), 2);
+        assert_eq!(map.count_corners(Point::new(1, 0)), 0);
+        assert_eq!(map.count_corners(Point::new(1, 1)), 4);
     }
 
     #[test]
@@ -145,7 +222,7 @@ mod tests {
         let map = Map::new(input).unwrap();
 
         let mut visited = HashSet::new();
-        assert_eq!(map.explore_area((0, 0), &mut visited), Some((8, 16, 8)));
-        assert_eq!(map.explore_area((1, 1), &mut visited), Some((1, 4, 4)));
+        assert_eq!(map.explore_area(Point::new(0, 0), &mut visited), Some((8, 16, 8)));
+        assert_eq!(map.explore_area(Point::new(1, 1), &mut visited), Some((1, 4, 4)));
     }
 }
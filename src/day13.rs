@@ -1,35 +1,45 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::answer::Answer;
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 #[derive(Debug)]
-struct Machine {
-    a: (i64, i64),
-    b: (i64, i64),
-    prize: (i64, i64),
+pub struct Machine {
+    pub a: (i64, i64),
+    pub b: (i64, i64),
+    pub prize: (i64, i64),
 }
 
 impl Machine {
-    fn new(group: &str) -> Option<Machine> {
+    fn new(p: &Parser, group: &str) -> Result<Machine, ParseError> {
         static BUTTON_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"X\+(\d+), Y\+(\d+)").unwrap());
         static PRIZE_REGEX: Lazy<Regex> = Lazy::new(|| Regex::new(r"X=(\d+), Y=(\d+)").unwrap());
 
         let mut lines = group.lines();
+        let mut last = group;
 
-        let parse_coords = |captures: regex::Captures| -> Option<(i64, i64)> {
-            Some((
-                captures.get(1)?.as_str().parse().ok()?,
-                captures.get(2)?.as_str().parse().ok()?,
-            ))
+        let mut parse_coords = |regex: &Regex, expected: &str| -> Result<(i64, i64), ParseError> {
+            let line = lines.next().ok_or_else(|| p.error_after(last, expected))?;
+            last = line;
+            let captures = regex
+                .captures(line)
+                .ok_or_else(|| p.error(line, expected))?;
+            Ok((p.number(&captures[1])?, p.number(&captures[2])?))
         };
 
-        let a = parse_coords(BUTTON_REGEX.captures(lines.next()?)?)?;
-        let b = parse_coords(BUTTON_REGEX.captures(lines.next()?)?)?;
-        let prize = parse_coords(PRIZE_REGEX.captures(lines.next()?)?)?;
+        let a = parse_coords(&BUTTON_REGEX, "a button like 'X+94, Y+34'")?;
+        let b = parse_coords(&BUTTON_REGEX, "a button like 'X+94, Y+34'")?;
+        let prize = parse_coords(&PRIZE_REGEX, "a prize like 'X=8400, Y=5400'")?;
 
-        Some(Machine { a, b, prize })
+        Ok(Machine { a, b, prize })
     }
 
-    fn get_coefficients(&self, target: (i64, i64)) -> Option<(i64, i64)> {
+    /// The cheapest presses of A and B that reach `target`, pressing neither more than `most`
+    /// times
+    fn get_coefficients(&self, target: (i64, i64), most: i64) -> Option<(i64, i64)> {
         // Extract vectors and target based on whether we're doing part 2
         let v1 = self.a;
         let v2 = self.b;
@@ -37,12 +47,26 @@ impl Machine {
         // Calculate determinant
         let det = v1.0 * v2.1 - v1.1 * v2.0;
 
+        // Parallel buttons can reach a prize on their line in many ways, or none off it
+        if det == 0 {
+            let (a, b, t) = if v1.0 != 0 || v2.0 != 0 {
+                (v1.0, v2.0, target.0)
+            } else {
+                (v1.1, v2.1, target.1)
+            };
+            return cheapest_along(a, b, t, most).filter(|&(a, b)| {
+                a * v1.0 + b * v2.0 == target.0 && a * v1.1 + b * v2.1 == target.1
+            });
+        }
+
         // Calculate coefficients using Cramer's rule
         let a = target.0 * v2.1 - target.1 * v2.0;
         let b = v1.0 * target.1 - v1.1 * target.0;
 
-        // Check if we have integer solutions
-        if a % det == 0 && b % det == 0 {
+        // Check if we have integer solutions, which can't press a button a negative number of
+        // times
+        let in_range = |presses: i64| (0..=most).contains(&presses);
+        if a % det == 0 && b % det == 0 && in_range(a / det) && in_range(b / det) {
             Some((a / det, b / det))
         } else {
             None
@@ -50,27 +74,170 @@ impl Machine {
     }
 }
 
-pub fn solution(input: &str) {
-    let mut part1 = 0;
-    let mut part2 = 0;
+/// The cheapest presses of buttons moving `a` and `b` along one line to get `target` along it,
+/// pressing neither more than `most` times
+fn cheapest_along(a: i64, b: i64, target: i64, most: i64) -> Option<(i64, i64)> {
+    let presses = match (a, b) {
+        (0, 0) => (target == 0).then_some((0, 0)),
+        (0, b) => (target % b == 0).then(|| (0, target / b)),
+        (a, 0) => (target % a == 0).then(|| (target / a, 0)),
+        (a, b) => {
+            // Press whichever button costs less for how far it goes as often as possible, then
+            // back off until the other can make up the rest, which repeats within as many
+            // presses as the other goes
+            let b_cheaper = a < 3 * b;
+            let (cheap, dear) = if b_cheaper { (b, a) } else { (a, b) };
+            let (cheap_presses, dear_presses) = (0..=most.min(target / cheap))
+                .rev()
+                .take(dear as usize)
+                .map(|n| (n, target - n * cheap))
+                .find(|&(_, rest)| rest % dear == 0)
+                .map(|(n, rest)| (n, rest / dear))?;
+            Some(if b_cheaper {
+                (dear_presses, cheap_presses)
+            } else {
+                (cheap_presses, dear_presses)
+            })
+        }
+    }?;
+    (presses.0 <= most && presses.1 <= most).then_some(presses)
+}
+
+/// The most either button is pressed to win a prize in part 1
+const MAX_PRESSES: i64 = 100;
+
+params! {
+    /// How far the prizes really are in part 2
+    offset: i64 = 10_000_000_000_000,
+}
+
+pub struct Arcade {
+    pub machines: Vec<Machine>,
+}
 
-    input
+pub fn parse(input: &str) -> Result<Arcade, ParseError> {
+    let p = Parser::new(input);
+    let machines = input
         .split("\n\n")
-        .map(|group| Machine::new(group).unwrap())
-        .for_each(|machine| {
-            if let Some((a, b)) = machine.get_coefficients(machine.prize) {
-                part1 += 3 * a + b;
-            }
+        .map(|group| Machine::new(&p, group))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Arcade { machines })
+}
 
+pub fn part1(arcade: &Arcade, _params: &Params) -> Answer {
+    let part1: i64 = arcade
+        .machines
+        .iter()
+        .filter_map(|machine| machine.get_coefficients(machine.prize, MAX_PRESSES))
+        .map(|(a, b)| 3 * a + b)
+        .sum();
+    part1.into()
+}
+
+pub fn part2(arcade: &Arcade, params: &Params) -> Answer {
+    let part2: i64 = arcade
+        .machines
+        .iter()
+        .filter_map(|machine| {
             let part2_prize = (
-                machine.prize.0 + 10_i64.pow(13),
-                machine.prize.1 + 10_i64.pow(13),
+                machine.prize.0 + params.offset,
+                machine.prize.1 + params.offset,
             );
-            if let Some((a, b)) = machine.get_coefficients(part2_prize) {
-                part2 += 3 * a + b;
-            }
-        });
+            machine.get_coefficients(part2_prize, i64::MAX)
+        })
+        .map(|(a, b)| 3 * a + b)
+        .sum();
+    part2.into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
 
-    println!("Part 1: {}", part1);
-    println!("Part 2: {}", part2);
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+",
+    params: &[],
+    part1: Some("480"),
+    part2: Some("875318608908"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    // Try every number of A presses, working out the B presses that would line up on X
+    fn cheapest(machine: &Machine, prize: (i64, i64), most: i64) -> Option<i64> {
+        (0..=most.min(prize.0 / machine.a.0))
+            .filter_map(|a| {
+                let left = prize.0 - a * machine.a.0;
+                let b = left / machine.b.0;
+                let lands = left % machine.b.0 == 0
+                    && b <= most
+                    && a * machine.a.1 + b * machine.b.1 == prize.1;
+                lands.then_some(3 * a + b)
+            })
+            .min()
+    }
+
+    fn oracle(arcade: &Arcade, offset: i64, most: i64) -> i64 {
+        arcade
+            .machines
+            .iter()
+            .filter_map(|machine| {
+                cheapest(machine, (machine.prize.0 + offset, machine.prize.1 + offset), most)
+            })
+            .sum()
+    }
+
+    #[test]
+    fn test_parallel_buttons() {
+        let arcade = parse(
+            "\
+Button A: X+2, Y+2
+Button B: X+1, Y+1
+Prize: X=10, Y=10
+
+Button A: X+2, Y+4
+Button B: X+3, Y+6
+Prize: X=12, Y=20
+",
+        )
+        .unwrap();
+        // Ten presses of B, and nothing for the prize off the buttons' line
+        assert_eq!(part1(&arcade, &Params::default()), 10.into());
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        let params = Params { offset: 5000 };
+        for (seed, input) in gen::samples(13, 30, 20) {
+            let arcade = parse(&input).unwrap();
+            assert_eq!(
+                part1(&arcade, &params),
+                oracle(&arcade, 0, MAX_PRESSES).into(),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                part2(&arcade, &params),
+                oracle(&arcade, 5000, i64::MAX).into(),
+                "seed {}",
+                seed
+            );
+        }
+    }
 }
@@ -1,51 +1,61 @@
-const WIDTH: isize = 101;
-const HEIGHT: isize = 103;
+use std::num::NonZeroUsize;
+
+use crate::answer::Answer;
+use crate::log::{self, debug, trace, Level};
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::point::Point;
+use crate::render::{self, Canvas, Rgb};
+use crate::solution::Example;
+
+params! {
+    /// Size of the room
+    width: NonZeroUsize = NonZeroUsize::new(101).unwrap(),
+    height: NonZeroUsize = NonZeroUsize::new(103).unwrap(),
+    /// How long to simulate for part 1
+    seconds: isize = 100,
+}
+
+impl Params {
+    /// The room's width and height, in the same terms as the robots' positions
+    fn room(&self) -> (isize, isize) {
+        (self.width.get() as isize, self.height.get() as isize)
+    }
+}
 
 #[derive(Debug, Clone)]
-struct Robot {
-    pos: (isize, isize),
-    vel: (isize, isize),
+pub struct Robot {
+    pub pos: Point,
+    pub vel: Point,
 }
 
 impl Robot {
-    fn new(line: &str) -> Self {
-        let mut parts = line.split(' ');
-
-        let pos = parts.next().unwrap();
-        let vel = parts.next().unwrap();
+    fn new(p: &Parser, line: &str) -> Result<Self, ParseError> {
+        let (pos, vel) = p.split_once(line, " ")?;
 
-        let extract = |s: &str| -> (isize, isize) {
-            let mut iter = s[2..].split(',').map(|x| x.parse::<isize>().unwrap());
-            (iter.next().unwrap(), iter.next().unwrap())
+        let extract = |s: &str, prefix: &str| -> Result<Point, ParseError> {
+            let (x, y) = p.split_once(p.strip_prefix(s, prefix)?, ",")?;
+            Ok(Point::new(p.number(x)?, p.number(y)?))
         };
 
-        let pos = extract(pos);
-        let vel = extract(vel);
+        let pos = extract(pos, "p=")?;
+        let vel = extract(vel, "v=")?;
 
-        Robot { pos, vel }
+        Ok(Robot { pos, vel })
     }
 
-    fn step(&mut self, steps: isize) {
-        self.pos.0 += self.vel.0 * steps;
-        self.pos.1 += self.vel.1 * steps;
-
-        self.pos.0 %= WIDTH;
-        self.pos.1 %= HEIGHT;
-
-        if (self.pos.0) < 0 {
-            self.pos.0 += WIDTH;
-        }
-        if (self.pos.1) < 0 {
-            self.pos.1 += HEIGHT;
-        }
+    fn step(&mut self, steps: isize, params: &Params) {
+        let (width, height) = params.room();
+        let pos = self.pos + self.vel * steps;
+        self.pos = Point::new(pos.x.rem_euclid(width), pos.y.rem_euclid(height));
     }
 
-    fn quadrant(&self) -> Option<usize> {
-        let x = self.pos.0;
-        let y = self.pos.1;
+    fn quadrant(&self, params: &Params) -> Option<usize> {
+        let Point { x, y } = self.pos;
 
-        let centre_x = WIDTH / 2;
-        let centre_y = HEIGHT / 2;
+        let (width, height) = params.room();
+        let centre_x = width / 2;
+        let centre_y = height / 2;
 
         if x < centre_x && y < centre_y {
             Some(0)
@@ -61,63 +71,224 @@ impl Robot {
     }
 }
 
-fn print_map(robots: &[Robot]) {
-    for y in 0..HEIGHT {
-        for x in 0..WIDTH {
-            let mut found = false;
-
-            for robot in robots {
-                if robot.pos == (x, y) {
-                    print!("#");
-                    found = true;
-                    break;
-                }
-            }
+/// The room with a `#` for every robot
+fn draw(robots: &[Robot], params: &Params) -> Canvas {
+    let mut canvas = Canvas::blank(params.width.get(), params.height.get());
+    for robot in robots {
+        canvas.glyph(robot.pos, '#');
+        canvas.colour(robot.pos, Rgb::GREEN);
+    }
+    canvas
+}
 
-            if !found {
-                print!(".");
-            }
-        }
-        println!();
+fn print_map(robots: &[Robot], params: &Params) {
+    if !log::enabled(Level::Debug) {
+        return;
     }
+
+    debug!("{}", draw(robots, params).for_stderr());
 }
 
-pub fn solution(input: &str) {
-    let mut robots: Vec<_> = input.lines().map(Robot::new).collect();
-    let robot_count = robots.len();
+pub struct Robots {
+    pub robots: Vec<Robot>,
+}
+
+pub fn parse(input: &str) -> Result<Robots, ParseError> {
+    let p = Parser::new(input);
+    let robots = p
+        .lines()
+        .map(|line| Robot::new(&p, line))
+        .collect::<Result<_, _>>()?;
 
-    // Part 1
+    Ok(Robots { robots })
+}
+
+pub fn part1(robots: &Robots, params: &Params) -> Answer {
     let mut quadrants = [0; 4];
-    robots.iter().cloned().for_each(|mut robot| {
-        robot.step(100);
-        if let Some(quadrant) = robot.quadrant() {
+    robots.robots.iter().cloned().for_each(|mut robot| {
+        robot.step(params.seconds, params);
+        if let Some(quadrant) = robot.quadrant(params) {
             quadrants[quadrant] += 1;
         }
     });
 
-    let part1 = quadrants.iter().product::<usize>();
-    println!("Part 1: {}", part1);
+    quadrants.iter().product::<usize>().into()
+}
+
+pub fn part2(robots: &Robots, params: &Params) -> Answer {
+    let mut robots = robots.robots.clone();
+    let robot_count = robots.len();
+    // Without robots every quadrant holds half of them at once
+    if robot_count == 0 {
+        return Answer::Unsolved;
+    }
 
-    // Part 2
-    for i in 1.. {
+    // Positions repeat after width * height steps
+    let (width, height) = params.room();
+    for i in 1..=width * height {
         let mut count: [usize; 4] = [0; 4];
 
         robots.iter_mut().for_each(|robot| {
-            robot.step(1);
+            robot.step(1, params);
 
-            if let Some(quadrant) = robot.quadrant() {
+            if let Some(quadrant) = robot.quadrant(params) {
                 count[quadrant] += 1;
             }
         });
 
         if i % 10000 == 0 {
-            println!("Iteration {}", i);
+            trace!("Iteration {}", i);
+        }
+        if render::frames_enabled() {
+            render::frame("robots", &draw(&robots, params));
         }
 
         if count.iter().any(|&x| x >= robot_count / 2) {
-            println!("Iteration {}", i);
-            print_map(&robots);
-            break;
+            print_map(&robots, params);
+            return i.into();
+        }
+    }
+
+    Answer::Unsolved
+}
+
+// The robots never form a tree in the example, so part 2 has no answer
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3
+",
+    params: &[("width", "11"), ("height", "7")],
+    part1: Some("12"),
+    part2: None,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::{self, Rng};
+
+    /// Every robot's position after each second in turn, wrapping one step at a time.
+    fn seconds(robots: &Robots, params: &Params) -> impl Iterator<Item = Vec<(isize, isize)>> {
+        let velocities: Vec<(isize, isize)> =
+            robots.robots.iter().map(|r| (r.vel.x, r.vel.y)).collect();
+        let start = robots.robots.iter().map(|r| (r.pos.x, r.pos.y)).collect();
+        let (width, height) = params.room();
+        std::iter::successors(Some(start), move |positions: &Vec<(isize, isize)>| {
+            let moved = positions
+                .iter()
+                .zip(&velocities)
+                .map(|(&(x, y), &(dx, dy))| {
+                    ((x + dx).rem_euclid(width), (y + dy).rem_euclid(height))
+                })
+                .collect();
+            Some(moved)
+        })
+    }
+
+    /// How many robots are in each quadrant, skipping those on the middle lines.
+    fn quadrants(positions: &[(isize, isize)], params: &Params) -> [usize; 4] {
+        let (width, height) = params.room();
+        let (mid_x, mid_y) = (width / 2, height / 2);
+        let mut counts = [0; 4];
+        for &(x, y) in positions {
+            if x != mid_x && y != mid_y {
+                counts[usize::from(x > mid_x) + 2 * usize::from(y > mid_y)] += 1;
+            }
+        }
+        counts
+    }
+
+    fn oracle1(robots: &Robots, params: &Params) -> usize {
+        let positions = seconds(robots, params).nth(params.seconds as usize).unwrap();
+        quadrants(&positions, params).iter().product()
+    }
+
+    /// Whether the room, drawn as robots per tile, shows a tree: two thirds of the robots inside
+    /// a triangle at most a fifth of the room tall, a tile wider either side on each row down.
+    fn shows_tree(positions: &[(isize, isize)], params: &Params) -> bool {
+        let (width, height) = params.room();
+        let rows = height / 5;
+        let needed = positions.len() * 2 / 3;
+        if needed == 0 {
+            return false;
+        }
+
+        // Those robots must fit within that many rows, which rules out most seconds cheaply
+        let mut ys: Vec<isize> = positions.iter().map(|&(_, y)| y).collect();
+        ys.sort_unstable();
+        if !ys.windows(needed).any(|w| w[needed - 1] - w[0] < rows) {
+            return false;
+        }
+
+        let mut room = vec![vec![0; width as usize]; height as usize];
+        for &(x, y) in positions {
+            room[y as usize][x as usize] += 1;
+        }
+        (0..height).any(|top| {
+            (0..width).any(|centre| {
+                let inside: usize = (0..rows.min(height - top))
+                    .map(|row| {
+                        let left = (centre - row).max(0) as usize;
+                        let right = (centre + row).min(width - 1) as usize;
+                        room[(top + row) as usize][left..=right].iter().sum::<usize>()
+                    })
+                    .sum();
+                inside >= needed
+            })
+        })
+    }
+
+    fn oracle2(robots: &Robots, params: &Params) -> Option<usize> {
+        seconds(robots, params)
+            .enumerate()
+            .skip(1)
+            .take(params.width.get() * params.height.get())
+            .find(|(_, positions)| shows_tree(positions, params))
+            .map(|(second, _)| second)
+    }
+
+    #[test]
+    fn test_no_robots() {
+        let robots = parse("").unwrap();
+        assert_eq!(part2(&robots, &Params::default()), Answer::Unsolved);
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        // A small room keeps the simulation quick, with enough robots in it that chance alone
+        // never crowds half of them into a quadrant, which part 2 relies on the tree to do
+        let (width, height) = (31, 29);
+        let params = Params {
+            width: NonZeroUsize::new(width).unwrap(),
+            height: NonZeroUsize::new(height).unwrap(),
+            ..Params::default()
+        };
+        for seed in 0..10 {
+            let input = gen::robots_in_room(&mut Rng::new(seed), 300, width as i64, height as i64);
+            let robots = parse(&input).unwrap();
+            assert_eq!(
+                part1(&robots, &params),
+                oracle1(&robots, &params).into(),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                part2(&robots, &params),
+                oracle2(&robots, &params).map_or(Answer::Unsolved, Answer::from),
+                "seed {}",
+                seed
+            );
         }
     }
 }
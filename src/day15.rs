@@ -1,91 +1,285 @@
 use std::collections::{HashSet, VecDeque};
 
-const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+use crate::answer::Answer;
+use crate::log::{self, debug, Level};
+use crate::parser::{ParseError, Parser};
+use crate::point::{Direction, Point};
+use crate::render::{self, Canvas, Rgb};
+use crate::solution::Example;
 
-pub fn solution(input: &str) {
-    let (map, directions) = input.split_once("\n\n").unwrap();
+pub struct Warehouse {
+    pub map: Vec<u8>,
+    pub width: isize,
+    pub height: isize,
+    pub robot: Point,
+    pub moves: Vec<Direction>,
+}
 
-    let width = map.find('\n').unwrap() as i32;
-    let pos = map.find('@').unwrap() as i32;
+pub fn parse(input: &str) -> Result<Warehouse, ParseError> {
+    let p = Parser::new(input);
+    let (map, directions) = p.sections()?;
 
-    let height = (map.len() as i32 + 1) / (width + 1);
-    let x = pos % (width + 1);
-    let y = pos / (width + 1);
+    let (width, height) = p.grid_size(map)?;
+    let (width, height) = (width as isize, height as isize);
+    let pos = map
+        .find('@')
+        .ok_or_else(|| p.error(map, "a robot '@' in the map"))? as isize;
 
-    let mut map1 = Vec::new();
-    let mut map2 = Vec::new();
+    let robot = Point::new(pos % (width + 1), pos / (width + 1));
 
-    (0..height)
-        .flat_map(|y| (0..width).map(move |x| (x, y)))
-        .for_each(|(x, y)| {
-            match map.as_bytes()[(y * (width + 1) + x) as usize] {
-                b'O' => {
-                    map1.push(b'[');
-                    map2.extend([b'[', b']']);
-                }
-                b => {
-                    map1.push(b);
-                    map2.extend([b; 2]);
-                }
-            };
-        });
+    let map = map.bytes().filter(|&b| b != b'\n').collect();
+
+    let moves = directions
+        .char_indices()
+        .filter(|&(_, c)| c != '\n')
+        .map(|(i, c)| {
+            Direction::from_char(c).ok_or_else(|| {
+                p.error(
+                    &directions[i..i + c.len_utf8()],
+                    "a move: '^', '>', 'v' or '<'",
+                )
+            })
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Warehouse {
+        map,
+        width,
+        height,
+        robot,
+        moves,
+    })
+}
+
+/// The warehouse `w` tiles wide, with walls, boxes and the robot picked out
+fn draw(map: &[u8], w: isize) -> Canvas {
+    let mut canvas = Canvas::blank(w as usize, map.len() / w as usize);
+    for (i, &b) in map.iter().enumerate() {
+        let pos = Point::new(i as isize % w, i as isize / w);
+        canvas.glyph(pos, b as char);
+        match b {
+            b'#' => canvas.colour(pos, Rgb::GREY),
+            b'[' | b']' => canvas.colour(pos, Rgb::YELLOW),
+            b'@' => canvas.colour(pos, Rgb::RED),
+            _ => {}
+        }
+    }
+    canvas
+}
 
+/// Run every move on a map `w` tiles wide where boxes are `s` tiles wide, returning the GPS sum
+fn solve(warehouse: &Warehouse, mut map: Vec<u8>, w: isize, mut robot: Point, s: usize) -> i64 {
+    let index = |pos: Point| (pos.y * w + pos.x) as usize;
+    let animation = format!("warehouse{}", s);
     let mut q = VecDeque::new();
     let mut set = HashSet::new();
 
-    let dirs: Vec<_> = directions
-        .chars()
-        .filter(|&c| c != '\n')
-        .map(|c| match c {
-            '^' => DIRECTIONS[0],
-            '>' => DIRECTIONS[1],
-            'v' => DIRECTIONS[2],
-            '<' => DIRECTIONS[3],
-            _ => panic!("Invalid direction: {}", c),
-        })
-        .collect();
+    'outer: for &direction in &warehouse.moves {
+        q.clear();
+        set.clear();
+        q.push_back(robot);
 
-    let mut solve = |mut map: Vec<u8>, w, mut x, mut y, s| {
-        'outer: for &(dx, dy) in &dirs {
-            q.clear();
-            set.clear();
-            q.push_back([x, y]);
-
-            while let Some((x, y, x2, y2)) = q.pop_front().map(|[x, y]| (x, y, x + dx, y + dy)) {
-                if set.insert([x, y]) {
-                    match map[(y2 * w + x2) as usize] {
-                        b'#' => continue 'outer,
-                        b'[' => q.extend(&[[x2, y2], [x2 + 1, y2]][..s]),
-                        b']' => q.extend([[x2, y2], [x2 - 1, y2]]),
-                        _ => {}
-                    }
+        while let Some(pos) = q.pop_front() {
+            let next = pos + direction;
+            if set.insert(pos) {
+                match map[index(next)] {
+                    b'#' => continue 'outer,
+                    b'[' => q.extend(&[next, next + Direction::Right][..s]),
+                    b']' => q.extend([next, next + Direction::Left]),
+                    _ => {}
                 }
             }
+        }
 
-            while !set.is_empty() {
-                let items: Vec<_> = set.iter().cloned().collect();
-                for [x, y] in items {
-                    if !set.contains(&[x + dx, y + dy]) {
-                        map.swap((y * w + x) as usize, ((y + dy) * w + x + dx) as usize);
-                        set.remove(&[x, y]);
-                    }
+        while !set.is_empty() {
+            let items: Vec<_> = set.iter().cloned().collect();
+            for pos in items {
+                if !set.contains(&(pos + direction)) {
+                    map.swap(index(pos), index(pos + direction));
+                    set.remove(&pos);
                 }
             }
+        }
+
+        robot += direction;
 
-            (x, y) = (x + dx, y + dy);
+        if render::frames_enabled() {
+            render::frame(&animation, &draw(&map, w));
         }
+    }
+
+    if log::enabled(Level::Debug) {
+        debug!("{}", draw(&map, w).for_stderr());
+    }
 
-        let str = std::str::from_utf8(&map).unwrap();
-        for y in 0..height {
-            println!("{}", &str[(y * w) as usize..(y * w + w) as usize]);
+    map.iter()
+        .enumerate()
+        .map(|(i, &c)| i64::from(c == b'[') * (100 * (i as isize / w) + i as isize % w) as i64)
+        .sum::<i64>()
+}
+
+pub fn part1(warehouse: &Warehouse) -> Answer {
+    let map1 = warehouse
+        .map
+        .iter()
+        .map(|&b| if b == b'O' { b'[' } else { b })
+        .collect();
+
+    solve(warehouse, map1, warehouse.width, warehouse.robot, 1).into()
+}
+
+pub fn part2(warehouse: &Warehouse) -> Answer {
+    let map2 = warehouse
+        .map
+        .iter()
+        .flat_map(|&b| match b {
+            b'O' => [b'[', b']'],
+            b => [b; 2],
+        })
+        .collect();
+
+    let robot = Point::new(warehouse.robot.x * 2, warehouse.robot.y);
+    solve(warehouse, map2, warehouse.width * 2, robot, 2).into()
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<
+",
+        params: &[],
+        part1: Some("2028"),
+        part2: None,
+    },
+    Example {
+        input: "\
+##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^
+",
+        params: &[],
+        part1: Some("10092"),
+        part2: Some("9021"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+    use crate::grid::Grid;
+
+    /// The warehouse with the robot's square left empty, `scale` squares to each tile.
+    fn floor(warehouse: &Warehouse, scale: usize) -> Grid<char> {
+        let cells = warehouse
+            .map
+            .iter()
+            .flat_map(|&b| {
+                let tiles: &[char] = match (b, scale) {
+                    (b'O', 2) => &['[', ']'],
+                    (b'@', _) => &['.', '.'],
+                    (b'#', _) => &['#', '#'],
+                    (b'O', _) => &['O', 'O'],
+                    _ => &['.', '.'],
+                };
+                tiles[..scale].to_vec()
+            })
+            .collect();
+        let width = warehouse.width as usize * scale;
+        Grid::new(width, warehouse.height as usize, cells)
+    }
+
+    fn gps(grid: &Grid<char>, box_glyph: char) -> isize {
+        grid.find_all(&box_glyph).map(|pos| 100 * pos.y + pos.x).sum()
+    }
+
+    // Look along the row of boxes ahead, and if there's space after it, move the first box there
+    fn oracle1(warehouse: &Warehouse) -> isize {
+        let mut grid = floor(warehouse, 1);
+        let mut robot = warehouse.robot;
+        for &direction in &warehouse.moves {
+            let mut end = robot + direction;
+            while grid[end] == 'O' {
+                end += direction;
+            }
+            if grid[end] == '.' {
+                grid[end] = grid[robot + direction];
+                grid[robot + direction] = '.';
+                robot += direction;
+            }
         }
+        gps(&grid, 'O')
+    }
 
-        map.iter()
-            .enumerate()
-            .map(|(i, &c)| i64::from(c == b'[') * i64::from(100 * (i as i32 / w) + i as i32 % w))
-            .sum::<i64>()
-    };
+    // Gather every box touching another in the way, then move them all if nothing's blocked
+    fn oracle2(warehouse: &Warehouse) -> isize {
+        let mut grid = floor(warehouse, 2);
+        let mut robot = Point::new(warehouse.robot.x * 2, warehouse.robot.y);
+        'moves: for &direction in &warehouse.moves {
+            let mut pushed: Vec<Point> = Vec::new();
+            let mut frontier = vec![robot];
+            while let Some(pos) = frontier.pop() {
+                let next = pos + direction;
+                let halves = match grid[next] {
+                    '#' => continue 'moves,
+                    '[' => [next, next + Direction::Right],
+                    ']' => [next + Direction::Left, next],
+                    _ => continue,
+                };
+                for half in halves {
+                    if !pushed.contains(&half) {
+                        pushed.push(half);
+                        frontier.push(half);
+                    }
+                }
+            }
 
-    println!("Part 1: {}", solve(map1, width, x, y, 1));
-    println!("Part 2: {}", solve(map2, width * 2, x * 2, y, 2));
+            // Lift every box off the floor, then put them down a step along
+            let lifted: Vec<(Point, char)> = pushed.iter().map(|&pos| (pos, grid[pos])).collect();
+            for &(pos, _) in &lifted {
+                grid[pos] = '.';
+            }
+            for &(pos, glyph) in &lifted {
+                grid[pos + direction] = glyph;
+            }
+            robot += direction;
+        }
+        gps(&grid, '[')
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(15, 10, 20) {
+            let warehouse = parse(&input).unwrap();
+            assert_eq!(part1(&warehouse), oracle1(&warehouse).into(), "seed {}", seed);
+            assert_eq!(part2(&warehouse), oracle2(&warehouse).into(), "seed {}", seed);
+        }
+    }
 }
@@ -1,243 +1,192 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap, HashSet},
-};
-
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
-const EAST: (isize, isize) = DIRECTIONS[1];
-
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    pos: (isize, isize),
-    dir: (isize, isize),
-}
+use std::collections::HashSet;
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost) // Note: reversed for min-heap
-    }
-}
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::parser::{ParseError, Parser};
+use crate::point::{Direction, Point};
+use crate::search::{self, Search};
+use crate::solution::Example;
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+// Reindeer start facing east
+const START_DIR: Direction = Direction::Right;
+
+/// A reindeer's position and facing direction
+pub type Node = (Point, Direction);
 
-struct Map {
-    tiles: Vec<char>,
-    width: usize,
-    height: usize,
+pub struct Map {
+    pub grid: Grid<char>,
+    pub start: Point,
+    pub end: Point,
 }
 
 impl Map {
-    fn new(input: &str) -> Option<Self> {
-        let tiles = input.lines().flat_map(|line| line.chars()).collect();
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            tiles,
-            width,
-            height,
-        })
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let p = Parser::new(input);
+        let grid = Grid::parse_chars(&p, input)?;
+        let start = grid
+            .find(&'S')
+            .ok_or_else(|| p.error(input, "a start 'S' in the map"))?;
+        let end = grid
+            .find(&'E')
+            .ok_or_else(|| p.error(input, "an end 'E' in the map"))?;
+        Ok(Self { grid, start, end })
     }
 
-    fn get_tile(&self, (x, y): (isize, isize)) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.tiles[y * self.width + x])
+    fn rotation_cost(from: Direction, to: Direction) -> usize {
+        from.turns_to(to) * 1000
     }
 
-    fn find(&self, c: char) -> impl Iterator<Item = (isize, isize)> + '_ {
-        self.tiles
-            .iter()
-            .enumerate()
-            .flat_map(move |(i, &tile)| {
-                if tile == c {
-                    Some((i % self.width, i / self.width))
-                } else {
-                    None
-                }
-            })
-            .map(|(x, y)| (x as isize, y as isize))
+    /// Every move from a node: a step in any direction, turning first if need be
+    fn moves(&self, (pos, dir): Node) -> impl Iterator<Item = (Node, usize)> + '_ {
+        Direction::ALL.into_iter().filter_map(move |new_dir| {
+            let new_pos = pos + new_dir;
+            // Off the edge of a map without a wall around it is as good as a wall
+            match self.grid.get(new_pos) {
+                Some('#') | None => None,
+                Some(_) => Some(((new_pos, new_dir), Self::rotation_cost(dir, new_dir) + 1)),
+            }
+        })
     }
 
-    fn rotation_cost(from: (isize, isize), to: (isize, isize)) -> usize {
-        let from_idx = DIRECTIONS.iter().position(|&d| d == from).unwrap();
-        let to_idx = DIRECTIONS.iter().position(|&d| d == to).unwrap();
-
-        let clockwise = (4 + to_idx as isize - from_idx as isize) % 4;
-        let counterclockwise = (4 - clockwise) % 4;
-
-        let rotations = std::cmp::min(clockwise, counterclockwise) as usize;
-        rotations * 1000
+    /// The cheapest ways from the start to the end
+    fn search(&self) -> Search<Node> {
+        search::dijkstra(
+            (self.start, START_DIR),
+            |&node| self.moves(node),
+            |&(pos, _)| pos == self.end,
+        )
     }
+}
 
-    fn shortest_path(
-        &self,
-        start: (isize, isize),
-        start_dir: (isize, isize),
-        end: (isize, isize),
-    ) -> Option<usize> {
-        let mut costs: HashMap<((isize, isize), (isize, isize)), usize> = HashMap::new();
-
-        let mut queue = BinaryHeap::new();
-        queue.push(State {
-            cost: 0,
-            pos: start,
-            dir: start_dir,
-        });
-
-        costs.insert((start, start_dir), 0);
-
-        while let Some(State { cost, pos, dir }) = queue.pop() {
-            // Skip if we've found a better path
-            if let Some(&best) = costs.get(&(pos, dir)) {
-                if cost > best {
-                    continue;
-                }
-            }
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Map::new(input)
+}
 
-            // Check end condition
-            if pos == end {
-                return Some(cost);
-            }
+pub fn part1(map: &Map) -> Answer {
+    // Find shortest path
+    map.search()
+        .goal_cost()
+        .map_or(Answer::Unsolved, Answer::from)
+}
 
-            // Try each direction
-            for &new_dir in DIRECTIONS.iter() {
-                let new_pos = (pos.0 + new_dir.0, pos.1 + new_dir.1);
-
-                match self.get_tile(new_pos) {
-                    Some('#') => continue,
-                    Some(_) => {
-                        let rotation_cost = Self::rotation_cost(dir, new_dir);
-                        let new_cost = cost + rotation_cost + 1;
-
-                        // Only visit if we haven't found a better path to this state
-                        if new_cost < *costs.get(&(new_pos, new_dir)).unwrap_or(&usize::MAX) {
-                            costs.insert((new_pos, new_dir), new_cost);
-                            queue.push(State {
-                                cost: new_cost,
-                                pos: new_pos,
-                                dir: new_dir,
-                            });
-                        }
-                    }
-                    _ => panic!("Out of bounds"),
-                }
-            }
-        }
+pub fn part2(map: &Map) -> Answer {
+    // Every tile on any of the best paths, whichever way the reindeer faced on it
+    let tiles: HashSet<Point> = map.search().optimal().iter().map(|&(pos, _)| pos).collect();
+    tiles.len().into()
+}
 
-        None
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############
+",
+    params: &[],
+    part1: Some("7036"),
+    part2: Some("45"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::gen;
+
+    /// The moves out of each node: a step forward, or a turn either way.
+    fn moves(map: &Map, (pos, dir): Node) -> Vec<(Node, usize)> {
+        let mut moves = vec![((pos, dir.turn_left()), 1000), ((pos, dir.turn_right()), 1000)];
+        if map.grid[pos + dir] != '#' {
+            moves.push(((pos + dir, dir), 1));
+        }
+        moves
     }
 
-    fn optimal_tiles(
-        &self,
-        start: (isize, isize),
-        start_dir: (isize, isize),
-        end: (isize, isize),
-    ) -> Option<usize> {
-        let mut costs: HashMap<((isize, isize), (isize, isize)), usize> = HashMap::new();
-        let mut previous: HashMap<
-            ((isize, isize), (isize, isize)),
-            Vec<((isize, isize), (isize, isize))>,
-        > = HashMap::new();
-        let mut queue = BinaryHeap::new();
-
-        queue.push(State {
-            cost: 0,
-            pos: start,
-            dir: start_dir,
-        });
-        costs.insert((start, start_dir), 0);
-
-        while let Some(State { cost, pos, dir }) = queue.pop() {
-            if let Some(&best) = costs.get(&(pos, dir)) {
-                if cost > best {
-                    continue;
-                }
-            }
-
-            for &new_dir in DIRECTIONS.iter() {
-                let new_pos = (pos.0 + new_dir.0, pos.1 + new_dir.1);
-                match self.get_tile(new_pos) {
-                    Some('#') => continue,
-                    Some(_) => {
-                        let rotation_cost = Self::rotation_cost(dir, new_dir);
-                        let new_cost = cost + rotation_cost + 1;
-
-                        if new_cost <= *costs.get(&(new_pos, new_dir)).unwrap_or(&usize::MAX) {
-                            costs.insert((new_pos, new_dir), new_cost);
-
-                            // There can be multiple paths to the same state
-                            let path = previous.entry((new_pos, new_dir)).or_default();
-                            if !path.contains(&(pos, dir)) {
-                                path.push((pos, dir));
-                            }
-
-                            queue.push(State {
-                                cost: new_cost,
-                                pos: new_pos,
-                                dir: new_dir,
-                            });
-                        }
+    /// The cheapest cost from `sources` to every node, found by relaxing every move over and
+    /// over until nothing gets cheaper, following moves backwards if `reverse` is set.
+    fn costs(map: &Map, sources: &[Node], reverse: bool) -> HashMap<Node, usize> {
+        let nodes: Vec<Node> = map
+            .grid
+            .positions()
+            .filter(|&pos| map.grid[pos] != '#')
+            .flat_map(|pos| Direction::ALL.map(|dir| (pos, dir)))
+            .collect();
+        let mut costs: HashMap<Node, usize> = sources.iter().map(|&node| (node, 0)).collect();
+        loop {
+            let mut changed = false;
+            for &node in &nodes {
+                for (next, cost) in moves(map, node) {
+                    let (from, to) = if reverse { (next, node) } else { (node, next) };
+                    let Some(&from_cost) = costs.get(&from) else {
+                        continue;
+                    };
+                    if costs.get(&to).is_none_or(|&to_cost| from_cost + cost < to_cost) {
+                        costs.insert(to, from_cost + cost);
+                        changed = true;
                     }
-                    _ => panic!("Out of bounds"),
                 }
             }
+            if !changed {
+                return costs;
+            }
         }
+    }
 
-        // Backtrack to find all tiles on all optimal paths
-        let min_cost = costs
-            .iter()
-            .filter(|((pos, _), _)| *pos == end)
-            .map(|(_, &cost)| cost)
-            .min()?;
+    fn oracle(map: &Map) -> (usize, usize) {
+        let start = (map.grid.find(&'S').unwrap(), START_DIR);
+        let end = map.grid.find(&'E').unwrap();
+        let ends = Direction::ALL.map(|dir| (end, dir));
 
-        let mut optimal_tiles: HashSet<(isize, isize)> = HashSet::new();
-        let mut to_visit = Vec::new();
+        let from_start = costs(map, &[start], false);
+        let to_end = costs(map, &ends, true);
+        let best = ends.iter().filter_map(|end| from_start.get(end)).min().copied().unwrap();
 
-        // Collect all end states with minimal cost
-        for ((pos, dir), &cost) in &costs {
-            if *pos == end && cost == min_cost {
-                to_visit.push((*pos, *dir));
-            }
-        }
+        // A tile is on a best path if the cheapest way through it costs no more than the best
+        let tiles: HashSet<Point> = from_start
+            .iter()
+            .filter(|&(node, cost)| to_end.get(node).is_some_and(|left| cost + left == best))
+            .map(|(&(pos, _), _)| pos)
+            .collect();
+        (best, tiles.len())
+    }
 
-        while let Some((pos, dir)) = to_visit.pop() {
-            optimal_tiles.insert(pos);
-            if let Some(prev_states) = previous.get(&(pos, dir)) {
-                for &prev in prev_states {
-                    if !optimal_tiles.contains(&prev.0) {
-                        to_visit.push(prev);
-                    }
-                }
-            }
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(16, 11, 20) {
+            let map = parse(&input).unwrap();
+            let (best, tiles) = oracle(&map);
+            assert_eq!(part1(&map), best.into(), "seed {}", seed);
+            assert_eq!(part2(&map), tiles.into(), "seed {}", seed);
         }
-
-        Some(optimal_tiles.len())
     }
-}
-
-pub fn solution(input: &str) {
-    let map = Map::new(input).unwrap();
-
-    // Find start and end pos
-    let start = map.find('S').next().unwrap();
-    let end = map.find('E').next().unwrap();
 
-    // Find shortest path
-    let part1 = map.shortest_path(start, EAST, end).unwrap();
-    println!("Part 1: {}", part1);
-
-    let part2 = map.optimal_tiles(start, EAST, end).unwrap();
-    println!("Part 2: {}", part2);
+    #[test]
+    fn test_cheaper_path_replaces_others() {
+        // Both ways round reach the end, but going right first costs 4 more, so only the 13 tiles
+        // going up are on a best path
+        let input = "\
+#########
+#......E#
+###.#.###
+#.#.#...#
+#.#.#.#.#
+#...#.#.#
+#.#####.#
+#S......#
+#########
+";
+        assert_eq!(part2(&parse(input).unwrap()), 13.into());
+    }
 }
@@ -1,3 +1,8 @@
+use crate::answer::Answer;
+use crate::log::trace;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 const ADV: u8 = 0;
 const BXL: u8 = 1;
 const BST: u8 = 2;
@@ -15,7 +20,8 @@ fn combo(reg: &[u64; 3], n: u8) -> u64 {
     }
 }
 
-fn run(mut reg: [u64; 3], program: &[u8], out: &mut Vec<u8>) {
+/// Run `program` from the given registers, appending anything it outputs to `out`.
+pub fn run(mut reg: [u64; 3], program: &[u8], out: &mut Vec<u8>) {
     let mut pc = 0;
 
     while pc < program.len() {
@@ -63,52 +69,191 @@ fn run(mut reg: [u64; 3], program: &[u8], out: &mut Vec<u8>) {
     }
 }
 
-pub fn solution(input: &str) {
-    let (reg, program) = input.split_once("\n\n").unwrap();
+pub struct Computer {
+    pub registers: [u64; 3],
+    pub program: Vec<u8>,
+}
+
+pub fn parse(input: &str) -> Result<Computer, ParseError> {
+    let p = Parser::new(input);
+    let (reg, program) = p.sections()?;
 
-    let mut reg_iter = reg
+    let registers = reg
         .lines()
-        .map(|line| line.split_once(": ").unwrap().1)
-        .map(|val| val.parse::<u64>().unwrap());
-    let mut registers: [u64; 3] = std::array::from_fn(|_| reg_iter.next().unwrap());
-
-    let program: Vec<_> = program
-        .split_once(": ")
-        .unwrap()
-        .1
+        .map(|line| p.number::<u64>(p.split_once(line, ": ")?.1))
+        .collect::<Result<Vec<_>, _>>()?;
+    let registers: [u64; 3] = registers
+        .try_into()
+        .map_err(|_| p.error(reg, "three registers"))?;
+
+    let (_, ops) = p.split_once(program.trim(), ": ")?;
+    let program = ops
         .split(',')
-        .map(|op| op.parse::<u8>().unwrap())
-        .collect();
+        .map(|op| match p.number::<u8>(op)? {
+            n if n < 8 => Ok(n),
+            _ => Err(p.error(op, "a 3-bit number")),
+        })
+        .collect::<Result<_, _>>()?;
 
-    let mut out = Vec::new();
-    run(registers, &program, &mut out);
+    Ok(Computer { registers, program })
+}
 
-    println!(
-        "Part 1: {}",
-        out.iter()
-            .map(|val| val.to_string())
-            .collect::<Vec<_>>()
-            .join(",")
-    );
+pub fn part1(computer: &Computer) -> Answer {
+    let mut out = Vec::new();
+    run(computer.registers, &computer.program, &mut out);
 
-    // TODO: Should reverse engineer the program to solve this properly
-    let mut total = 0;
-    for (i, n) in program.iter().rev().enumerate() {
-        println!("Digit: {}", i);
+    out.iter()
+        .map(|val| val.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+        .into()
+}
 
-        total <<= 3;
-        registers[0] = total;
+/// The lowest register A that makes `program` output itself, given the other registers.
+///
+/// Like every real program, it must output once per loop and shift A three bits each time,
+/// so A can be built three bits at a time, from the last output back to the first.
+pub fn quine(registers: [u64; 3], program: &[u8]) -> Option<u64> {
+    fn search(registers: [u64; 3], program: &[u8], a: u64, matched: usize) -> Option<u64> {
+        if matched == program.len() {
+            return Some(a);
+        }
+        trace!("Digit: {}", matched);
 
-        loop {
+        // Digits are tried lowest first, so the first A to match every output is the lowest
+        let tail = &program[program.len() - matched - 1..];
+        let mut out = Vec::new();
+        (0..8).find_map(|bits| {
+            let candidate = a << 3 | bits;
             out.clear();
-            run(registers, &program, &mut out);
-            if out[0] == *n {
-                break;
+            run([candidate, registers[1], registers[2]], program, &mut out);
+            if candidate == 0 || out != tail {
+                return None;
+            }
+            search(registers, program, candidate, matched + 1)
+        })
+    }
+
+    search(registers, program, 0, 0)
+}
+
+pub fn part2(computer: &Computer) -> Answer {
+    quine(computer.registers, &computer.program).map_or(Answer::Unsolved, Answer::from)
+}
+
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0
+",
+        params: &[],
+        part1: Some("4,6,3,5,6,3,5,2,1,0"),
+        part2: None,
+    },
+    Example {
+        input: "\
+Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0
+",
+        params: &[],
+        part1: None,
+        part2: Some("117440"),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::{self, Rng};
+
+    // Another reading of the instruction set, written from scratch, giving each output as it's
+    // made so a run can be abandoned as soon as it goes wrong
+    fn execute(mut a: u64, mut b: u64, mut c: u64, program: &[u8]) -> impl Iterator<Item = u8> + '_ {
+        let mut ip = 0;
+        std::iter::from_fn(move || {
+            while ip + 1 < program.len() {
+                let literal = u64::from(program[ip + 1]);
+                let combo = match literal {
+                    4 => a,
+                    5 => b,
+                    6 => c,
+                    n => n,
+                };
+                ip += 2;
+                match program[ip - 2] {
+                    0 => a >>= combo,
+                    1 => b ^= literal,
+                    2 => b = combo & 7,
+                    3 if a != 0 => ip = literal as usize,
+                    3 => {}
+                    4 => b ^= c,
+                    5 => return Some((combo & 7) as u8),
+                    6 => b = a >> combo,
+                    _ => c = a >> combo,
+                }
             }
+            None
+        })
+    }
+
+    fn oracle1(computer: &Computer) -> String {
+        let [a, b, c] = computer.registers;
+        let out: Vec<String> = execute(a, b, c, &computer.program)
+            .map(|n| n.to_string())
+            .collect();
+        out.join(",")
+    }
+
+    // Run every A that could print as many numbers as the program has, lowest first
+    fn oracle2(registers: [u64; 3], program: &[u8]) -> Option<u64> {
+        let [_, b, c] = registers;
+        (1..8u64.pow(program.len() as u32))
+            .find(|&a| execute(a, b, c, program).eq(program.iter().copied()))
+    }
+
+    /// A program short enough to scan every A for, shaped like the real ones: output and shift
+    /// A three bits in either order, then loop while A is left. With `mix`, A is shifted first
+    /// and copied into the register output, which always has a quine for the scan to stop at.
+    fn short_program(rng: &mut Rng, mix: bool) -> Vec<u8> {
+        let mut body = if mix {
+            let (copy, register) = [([2, 4], 5), ([6, 0], 5), ([7, 0], 6)][rng.below(3) as usize];
+            vec![[0, 3], copy, [5, register]]
+        } else {
+            let mut body = vec![[0, 3], [5, rng.range(4..7) as u8]];
+            rng.shuffle(&mut body);
+            body
+        };
+        body.push([3, 0]);
+        body.concat()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(17, 6, 20) {
+            let computer = parse(&input).unwrap();
+            assert_eq!(part1(&computer), oracle1(&computer).into(), "seed {}", seed);
+        }
 
-            total += 1;
-            registers[0] = total;
+        // Scanning a program of eight numbers takes a while, so only try a couple
+        for seed in 0..8 {
+            let mut rng = Rng::new(seed);
+            let computer = Computer {
+                registers: [0, rng.below(8), rng.below(8)],
+                program: short_program(&mut rng, seed % 4 == 0),
+            };
+            assert_eq!(
+                part2(&computer),
+                oracle2(computer.registers, &computer.program).map_or(Answer::Unsolved, Answer::from),
+                "seed {}",
+                seed
+            );
         }
     }
-    println!("Part 2: {}", total);
 }
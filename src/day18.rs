@@ -1,134 +1,205 @@
-use std::{
-    cmp::Ordering,
-    collections::{BinaryHeap, HashMap},
-};
-
-const DIRECTIONS: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::point::Point;
+use crate::search;
+use crate::solution::Example;
+
+params! {
+    /// Width and height of the memory space
+    size: usize = 71,
+    /// How many bytes fall before part 1
+    fallen: usize = 1024,
+}
 
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    pos: (isize, isize),
+pub struct Map {
+    pub grid: Grid<char>,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost) // Note: reversed for min-heap
+impl Map {
+    fn new(size: usize) -> Self {
+        Self {
+            grid: Grid::filled(size, size, '.'),
+        }
     }
-}
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+    fn shortest_path(&self) -> Option<usize> {
+        let end = Point::new(self.grid.width() as isize - 1, self.grid.height() as isize - 1);
+        search::bfs(
+            Point::ORIGIN,
+            |&pos| {
+                self.grid
+                    .neighbours4(pos)
+                    .filter(|&new_pos| self.grid[new_pos] != '#')
+            },
+            |&pos| pos == end,
+        )
+        .goal_cost()
     }
 }
 
-struct Map {
-    tiles: Vec<char>,
-    size: usize,
+pub struct Bytes {
+    pub positions: Vec<Point>,
 }
 
-impl Map {
-    fn new(size: usize) -> Option<Self> {
-        Some(Self {
-            tiles: vec!['.'; size * size],
-            size,
-        })
-    }
-
-    fn tile(&self, (x, y): (isize, isize)) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.size || y >= self.size {
-            return None;
+pub fn parse(input: &str, params: &Params) -> Result<Bytes, ParseError> {
+    let p = Parser::new(input);
+    let parse_line = |line: &str| -> Result<Point, ParseError> {
+        let (x, y) = p.split_once(line, ",")?;
+        let (x, y): (usize, usize) = (p.number(x)?, p.number(y)?);
+        if x >= params.size || y >= params.size {
+            return Err(p.error(
+                line,
+                format!("a byte inside the {0}x{0} memory space", params.size),
+            ));
         }
-        Some(self.tiles[y * self.size + x])
-    }
+        Ok(Point::new(x as isize, y as isize))
+    };
 
-    fn tile_mut(&mut self, (x, y): (isize, isize)) -> Option<&mut char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.size || y >= self.size {
-            return None;
-        }
-        Some(&mut self.tiles[y * self.size + x])
-    }
+    Ok(Bytes {
+        positions: p.lines().map(parse_line).collect::<Result<_, _>>()?,
+    })
+}
 
-    fn shortest_path(&self) -> Option<usize> {
-        let mut costs: HashMap<(isize, isize), usize> = HashMap::new();
-        let mut queue = BinaryHeap::new();
-
-        queue.push(State {
-            cost: 0,
-            pos: (0, 0),
-        });
-        costs.insert((0, 0), 0);
-
-        while let Some(State { cost, pos }) = queue.pop() {
-            if cost > *costs.get(&pos).unwrap_or(&usize::MAX) {
-                continue;
-            }
+pub fn part1(bytes: &Bytes, params: &Params) -> Answer {
+    let mut map = Map::new(params.size);
+    bytes.positions.iter().take(params.fallen).for_each(|&pos| {
+        map.grid[pos] = '#';
+    });
 
-            if pos == (self.size as isize - 1, self.size as isize - 1) {
-                return Some(cost);
-            }
+    map.shortest_path().map_or(Answer::Unsolved, Answer::from)
+}
 
-            for &new_dir in &DIRECTIONS {
-                let new_pos = (pos.0 + new_dir.0, pos.1 + new_dir.1);
+pub fn part2(bytes: &Bytes, params: &Params) -> Answer {
+    let mut map = Map::new(params.size);
+    let part2 = bytes.positions.iter().find(|&&pos| {
+        map.grid[pos] = '#';
+        map.shortest_path().is_none()
+    });
 
-                if let Some(tile) = self.tile(new_pos) {
-                    if tile == '#' {
-                        continue;
-                    }
+    part2.copied().map_or(Answer::Unsolved, Answer::from)
+}
 
-                    let new_cost = cost + 1;
-                    if new_cost < *costs.get(&new_pos).unwrap_or(&usize::MAX) {
-                        costs.insert(new_pos, new_cost);
-                        queue.push(State {
-                            cost: new_cost,
-                            pos: new_pos,
-                        });
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0
+",
+    params: &[("size", "7"), ("fallen", "12")],
+    part1: Some("22"),
+    part2: Some("6,1"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Rng;
+
+    /// Steps from the top left to every square reachable with the first `fallen` bytes down,
+    /// found by taking one more step out from everywhere reached until nothing new is.
+    fn distances(bytes: &Bytes, size: usize, fallen: usize) -> Grid<Option<usize>> {
+        let mut grid = Grid::filled(size, size, None);
+        grid[Point::ORIGIN] = Some(0);
+        for step in 0.. {
+            let reached: Vec<Point> = grid.find_all(&Some(step)).collect();
+            let mut found = false;
+            for pos in reached {
+                for next in grid.neighbours4(pos).collect::<Vec<_>>() {
+                    if grid[next].is_none() && !bytes.positions[..fallen].contains(&next) {
+                        grid[next] = Some(step + 1);
+                        found = true;
                     }
                 }
             }
+            if !found {
+                break;
+            }
         }
+        grid
+    }
 
-        None
+    fn exit(size: usize) -> Point {
+        Point::new(size as isize - 1, size as isize - 1)
     }
-}
 
-pub fn solution(input: &str) {
-    let parse_line = |line: &str| -> (isize, isize) {
-        let mut parts = line.split(',');
-        let x = parts.next().unwrap().parse::<isize>().unwrap();
-        let y = parts.next().unwrap().parse::<isize>().unwrap();
-        (x, y)
-    };
+    fn oracle1(bytes: &Bytes, params: &Params) -> Option<usize> {
+        distances(bytes, params.size, params.fallen)[exit(params.size)]
+    }
 
-    let mut map = Map::new(71).unwrap();
-    input.lines().take(1024).for_each(|line| {
-        let pos = parse_line(line);
-        *map.tile_mut(pos).unwrap() = '#';
-    });
+    // Drop the bytes one at a time, checking for a way through after each
+    fn oracle2(bytes: &Bytes, params: &Params) -> Option<Point> {
+        (1..=bytes.positions.len())
+            .find(|&fallen| distances(bytes, params.size, fallen)[exit(params.size)].is_none())
+            .map(|fallen| bytes.positions[fallen - 1])
+    }
 
-    let part1 = map.shortest_path().unwrap();
-    println!("Part 1: {}", part1);
+    #[test]
+    fn test_byte_outside() {
+        let params = Params { size: 5, fallen: 1 };
+        let err = parse("1,2\n4,4\n5,0\n", &params).err().unwrap();
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(err.expected, "a byte inside the 5x5 memory space");
+    }
 
-    let mut map = Map::new(71).unwrap();
-    let part2 = input.lines().map(parse_line).find(|pos| {
-        *map.tile_mut(*pos).unwrap() = '#';
-        map.shortest_path().is_none()
-    });
+    #[test]
+    fn test_no_way_out() {
+        let params = Params { size: 3, fallen: 3 };
+        let bytes = parse("0,1\n1,1\n2,1\n", &params).unwrap();
+        assert_eq!(part1(&bytes, &params), Answer::Unsolved);
+        assert_eq!(part2(&bytes, &params), Point::new(2, 1).into());
+    }
 
-    if let Some(part2) = part2 {
-        println!("Part 2: {},{}", part2.0, part2.1);
-    } else {
-        println!("Part 2: No solution found");
+    #[test]
+    fn test_matches_oracle() {
+        let params = Params { size: 9, fallen: 20 };
+        for seed in 0..20 {
+            // The generator only makes full size memory spaces, so scatter bytes on a small one
+            let mut rng = Rng::new(seed);
+            let mut squares: Vec<Point> = Grid::filled(params.size, params.size, ())
+                .positions()
+                .filter(|&pos| pos != Point::ORIGIN && pos != exit(params.size))
+                .collect();
+            rng.shuffle(&mut squares);
+            let bytes = Bytes {
+                positions: squares[..40].to_vec(),
+            };
+
+            assert_eq!(
+                part1(&bytes, &params),
+                oracle1(&bytes, &params).map_or(Answer::Unsolved, Answer::from),
+                "seed {}",
+                seed
+            );
+            assert_eq!(
+                part2(&bytes, &params),
+                oracle2(&bytes, &params).map_or(Answer::Unsolved, Answer::from),
+                "seed {}",
+                seed
+            );
+        }
     }
 }
@@ -1,19 +1,31 @@
 use std::collections::HashMap;
 
-fn design_possible(design: &str, patterns: &[&str]) -> bool {
-    patterns.iter().any(|pat| {
-        if !design.starts_with(pat) {
-            return false;
-        }
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
 
-        let design = &design[pat.len()..];
+fn design_possible<'a>(
+    design: &'a str,
+    patterns: &[&'a str],
+    memo: &mut HashMap<&'a str, bool>,
+) -> bool {
+    if design.is_empty() {
+        return true;
+    }
 
-        if design.is_empty() {
-            return true;
-        }
+    // Designs that can't be made would otherwise retry the same suffixes exponentially often
+    if let Some(&possible) = memo.get(design) {
+        return possible;
+    }
 
-        design_possible(design, patterns)
-    })
+    let possible = patterns.iter().any(|pattern| {
+        design
+            .strip_prefix(pattern)
+            .is_some_and(|remaining| design_possible(remaining, patterns, memo))
+    });
+
+    memo.insert(design, possible);
+    possible
 }
 
 fn count_arrangements<'a>(
@@ -32,8 +44,7 @@ fn count_arrangements<'a>(
     // Try each pattern as a potential start
     let mut total = 0;
     for pattern in patterns {
-        if design.starts_with(pattern) {
-            let remaining = &design[pattern.len()..];
+        if let Some(remaining) = design.strip_prefix(pattern) {
             total += count_arrangements(remaining, patterns, memo);
         }
     }
@@ -42,22 +53,147 @@ fn count_arrangements<'a>(
     total
 }
 
-pub fn solution(input: &str) {
-    let (patterns, designs) = input.split_once("\n\n").unwrap();
+pub struct Towels<'a> {
+    pub patterns: Vec<&'a str>,
+    pub designs: Vec<&'a str>,
+}
 
-    let patterns: Vec<_> = patterns.split(", ").collect();
-    let designs: Vec<_> = designs.lines().collect();
+/// Check `stripes` is a non-empty run of the five towel colours.
+fn stripes<'a>(p: &Parser, stripes: &'a str, expected: &str) -> Result<&'a str, ParseError> {
+    if stripes.is_empty() {
+        return Err(p.error(stripes, expected));
+    }
+    match stripes.find(|c| !"wubrg".contains(c)) {
+        Some(i) => Err(p.error(&stripes[i..], "a stripe colour: w, u, b, r or g")),
+        None => Ok(stripes),
+    }
+}
+
+pub fn parse(input: &str) -> Result<Towels<'_>, ParseError> {
+    let p = Parser::new(input);
+    let (patterns, designs) = p.sections()?;
 
-    let part1 = designs
+    // An empty pattern would let a design be split forever
+    let patterns = patterns
+        .split(", ")
+        .map(|pattern| stripes(&p, pattern, "a towel pattern"))
+        .collect::<Result<_, _>>()?;
+    let designs = designs
+        .lines()
+        .map(|design| stripes(&p, design, "a design"))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Towels { patterns, designs })
+}
+
+pub fn part1(towels: &Towels) -> Answer {
+    let mut memo = HashMap::new();
+    let part1 = towels
+        .designs
         .iter()
-        .filter(|design| design_possible(design, &patterns))
+        .filter(|design| design_possible(design, &towels.patterns, &mut memo))
         .count();
-    println!("Part 1: {}", part1);
+    part1.into()
+}
 
+pub fn part2(towels: &Towels) -> Answer {
     let mut memo = HashMap::new();
-    let part2 = designs
+    let part2 = towels
+        .designs
         .iter()
-        .map(|design| count_arrangements(design, &patterns, &mut memo))
+        .map(|design| count_arrangements(design, &towels.patterns, &mut memo))
         .sum::<usize>();
-    println!("Part 2: {}", part2);
+    part2.into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb
+",
+    params: &[],
+    part1: Some("6"),
+    part2: Some("16"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::gen;
+
+    // Cut the design at every combination of the gaps between its stripes, counting the cuttings
+    // whose pieces are all patterns
+    fn arrangements(design: &str, patterns: &HashSet<&str>) -> usize {
+        let gaps = design.len().saturating_sub(1);
+        (0..1u32 << gaps)
+            .filter(|cuts| {
+                let mut start = 0;
+                (1..=design.len())
+                    .filter(|&end| end == design.len() || cuts & 1 << (end - 1) != 0)
+                    .all(|end| {
+                        let piece = &design[start..end];
+                        start = end;
+                        patterns.contains(piece)
+                    })
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_bad_stripes() {
+        let err = parse("r, , b\n\nrb\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 4));
+        assert_eq!(err.expected, "a towel pattern");
+
+        let err = parse("r, b,\n\nrb\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 5));
+
+        let err = parse("r, bx\n\nrb\n").err().unwrap();
+        assert_eq!((err.line, err.column), (1, 5));
+        assert_eq!(err.expected, "a stripe colour: w, u, b, r or g");
+
+        let err = parse("r, b\n\nrb\nr?b\n").err().unwrap();
+        assert_eq!((err.line, err.column), (4, 2));
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(19, 20, 10) {
+            let towels = parse(&input).unwrap();
+            // Only the start of each design, so there are few enough ways to cut it to try every
+            // one, and no patterns of a single stripe, so some of those can't be made
+            let towels = Towels {
+                patterns: towels
+                    .patterns
+                    .iter()
+                    .copied()
+                    .filter(|pattern| pattern.len() > 1)
+                    .collect(),
+                designs: towels
+                    .designs
+                    .iter()
+                    .map(|design| &design[..design.len().min(12)])
+                    .collect(),
+            };
+            let patterns: HashSet<&str> = towels.patterns.iter().copied().collect();
+            let ways: Vec<usize> = towels
+                .designs
+                .iter()
+                .map(|design| arrangements(design, &patterns))
+                .collect();
+            let possible = ways.iter().filter(|&&n| n > 0).count();
+            assert_eq!(part1(&towels), possible.into(), "seed {}", seed);
+            assert_eq!(part2(&towels), ways.iter().sum::<usize>().into(), "seed {}", seed);
+        }
+    }
 }
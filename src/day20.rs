@@ -1,101 +1,57 @@
-use std::collections::{HashSet, VecDeque};
-
-const DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+use crate::answer::Answer;
+use crate::grid::Grid;
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::point::Point;
+use crate::search;
+use crate::solution::Example;
+
+params! {
+    /// The fewest picoseconds a cheat must save to count
+    saving: isize = 100,
+    /// How long a cheat can last in each part
+    part1_cheat: isize = 2,
+    part2_cheat: isize = 20,
+}
 
-struct Map {
-    tiles: Vec<char>,
-    width: usize,
-    height: usize,
+pub struct Map {
+    pub grid: Grid<char>,
+    pub start: Point,
+    pub end: Point,
 }
 
 impl Map {
-    fn new(input: &str) -> Option<Self> {
-        let tiles = input.lines().flat_map(|line| line.chars()).collect();
-        let width = input.lines().next()?.chars().count();
-        let height = input.lines().count();
-
-        Some(Self {
-            tiles,
-            width,
-            height,
-        })
-    }
-
-    fn index(&self, (x, y): (isize, isize)) -> Option<usize> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(y * self.width + x)
+    fn new(input: &str) -> Result<Self, ParseError> {
+        let p = Parser::new(input);
+        let grid = Grid::parse_chars(&p, input)?;
+        let start = grid
+            .find(&'S')
+            .ok_or_else(|| p.error(input, "a start 'S' in the map"))?;
+        let end = grid
+            .find(&'E')
+            .ok_or_else(|| p.error(input, "an end 'E' in the map"))?;
+        Ok(Self { grid, start, end })
     }
 
-    fn tile(&self, (x, y): (isize, isize)) -> Option<char> {
-        if x < 0 || y < 0 {
-            return None;
-        }
-        let x = x as usize;
-        let y = y as usize;
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.tiles[y * self.width + x])
-    }
-
-    fn find(&self, c: char) -> Option<(isize, isize)> {
-        self.tiles
-            .iter()
-            .enumerate()
-            .find_map(|(i, &tile)| {
-                if tile == c {
-                    Some((i % self.width, i / self.width))
-                } else {
-                    None
-                }
-            })
-            .map(|(x, y)| (x as isize, y as isize))
+    fn shortest_path(&self, start: Point, end: Point) -> Vec<Point> {
+        search::bfs(
+            start,
+            |&pos| {
+                self.grid
+                    .neighbours4(pos)
+                    .filter(|&next| self.grid[next] != '#')
+            },
+            |&pos| pos == end,
+        )
+        .path(&end)
+        .unwrap_or_default()
     }
 
-    fn shortest_path(&self, start: (isize, isize), end: (isize, isize)) -> Vec<(isize, isize)> {
-        let mut queue = VecDeque::new();
-        let mut visited = HashSet::new();
-        let mut result = Vec::new();
-
-        queue.push_back(start);
-
-        while let Some((x, y)) = queue.pop_back() {
-            if visited.contains(&(x, y)) {
-                continue;
-            }
-
-            visited.insert((x, y));
-            result.push((x, y));
-
-            if (x, y) == end {
-                return result;
-            }
-
-            for &(dx, dy) in &DIRECTIONS {
-                let next = (x + dx, y + dy);
-                if let Some(tile) = self.tile(next) {
-                    if tile != '#' {
-                        queue.push_front(next);
-                    }
-                }
-            }
-        }
-
-        Vec::new()
-    }
-
-    fn cheats(&self, path: &[(isize, isize)], max_distance: isize, min_diff: isize) -> usize {
-        let mut times = vec![-1; self.width * self.height];
+    fn cheats(&self, path: &[Point], max_distance: isize, min_diff: isize) -> usize {
+        let mut times = self.grid.map(|_| -1);
 
         for (i, &pos) in path.iter().enumerate() {
-            times[self.index(pos).unwrap()] = i as isize;
+            times[pos] = i as isize;
         }
 
         path.iter()
@@ -103,10 +59,10 @@ impl Map {
                 let mut count = 0;
                 for dx in -max_distance..=max_distance {
                     for dy in -max_distance..=max_distance {
-                        let distance = dx.abs() + dy.abs();
-                        let new_pos = (pos.0 + dx, pos.1 + dy);
+                        let new_pos = pos + Point::new(dx, dy);
+                        let distance = pos.manhattan(new_pos);
 
-                        let Some(new_idx) = self.index(new_pos) else {
+                        let Some(&target_time) = times.get(new_pos) else {
                             continue;
                         };
 
@@ -114,8 +70,7 @@ impl Map {
                             continue;
                         }
 
-                        let current_time = times[self.index(pos).unwrap()];
-                        let target_time = times[new_idx];
+                        let current_time = times[pos];
 
                         if target_time >= 0 && (current_time - target_time - distance) >= min_diff {
                             count += 1;
@@ -128,12 +83,104 @@ impl Map {
     }
 }
 
-pub fn solution(input: &str) {
-    let map = Map::new(input).unwrap();
-    let start = map.find('S').unwrap();
-    let end = map.find('E').unwrap();
-    let shortest_path = map.shortest_path(start, end);
+pub fn parse(input: &str) -> Result<Map, ParseError> {
+    Map::new(input)
+}
+
+fn race_path(map: &Map) -> Vec<Point> {
+    map.shortest_path(map.start, map.end)
+}
+
+pub fn part1(map: &Map, params: &Params) -> Answer {
+    map.cheats(&race_path(map), params.part1_cheat, params.saving)
+        .into()
+}
+
+pub fn part2(map: &Map, params: &Params) -> Answer {
+    map.cheats(&race_path(map), params.part2_cheat, params.saving)
+        .into()
+}
+
+// The example only has cheats saving far less than 100 picoseconds
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############
+",
+    params: &[("saving", "50")],
+    part1: Some("1"),
+    part2: Some("285"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::gen;
+
+    /// How far every square of track is from `from`, spreading out a step at a time.
+    fn distances(map: &Map, from: Point) -> HashMap<Point, isize> {
+        let mut distances = HashMap::from([(from, 0)]);
+        let mut edge = vec![from];
+        for step in 1.. {
+            edge = edge
+                .iter()
+                .flat_map(|&pos| map.grid.neighbours4(pos))
+                .filter(|&next| map.grid[next] != '#' && !distances.contains_key(&next))
+                .collect();
+            if edge.is_empty() {
+                break;
+            }
+            for &pos in &edge {
+                distances.insert(pos, step);
+            }
+        }
+        distances
+    }
 
-    println!("Part 1: {}", map.cheats(&shortest_path, 2, 100));
-    println!("Part 2: {}", map.cheats(&shortest_path, 20, 100));
+    // Time the race through every pair of track squares close enough to cheat between
+    fn oracle(map: &Map, cheat: isize, saving: isize) -> usize {
+        let from_start = distances(map, map.grid.find(&'S').unwrap());
+        let to_end = distances(map, map.grid.find(&'E').unwrap());
+        let honest = from_start[&map.grid.find(&'E').unwrap()];
+
+        let mut count = 0;
+        for (&a, &before) in &from_start {
+            for (&b, &after) in &to_end {
+                let skipped = a.manhattan(b);
+                if skipped <= cheat && honest - (before + skipped + after) >= saving {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        let params = Params {
+            saving: 4,
+            part1_cheat: 2,
+            part2_cheat: 6,
+        };
+        for (seed, input) in gen::samples(20, 15, 20) {
+            let map = parse(&input).unwrap();
+            assert_eq!(part1(&map, &params), oracle(&map, 2, 4).into(), "seed {}", seed);
+            assert_eq!(part2(&map, &params), oracle(&map, 6, 4).into(), "seed {}", seed);
+        }
+    }
 }
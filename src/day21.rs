@@ -1,71 +1,42 @@
 use itertools::Itertools;
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+
+use crate::answer::Answer;
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::point::{Direction, Point};
+use crate::search;
+use crate::solution::Example;
 
 const DOOR: [&str; 4] = ["789", "456", "123", "#0A"];
 const DPAD: [&str; 2] = ["#^A", "<v>"];
 
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
-impl Direction {
-    const ALL: [Self; 4] = [Self::Up, Self::Down, Self::Left, Self::Right];
-
-    fn to_char(&self) -> char {
-        match self {
-            Self::Up => '^',
-            Self::Down => 'v',
-            Self::Left => '<',
-            Self::Right => '>',
-        }
-    }
-
-    fn to_delta(&self) -> (isize, isize) {
-        match self {
-            Self::Up => (-1, 0),
-            Self::Down => (1, 0),
-            Self::Left => (0, -1),
-            Self::Right => (0, 1),
-        }
-    }
-}
-
-fn search(coords: &HashMap<(isize, isize), char>, a: char, b: char) -> Vec<String> {
-    let inv: HashMap<char, (isize, isize)> = coords.iter().map(|(&pos, &ch)| (ch, pos)).collect();
+/// All shortest move sequences from key `a` to key `b`
+fn search(coords: &HashMap<Point, char>, a: char, b: char) -> Vec<String> {
+    let inv: HashMap<char, Point> = coords.iter().map(|(&pos, &ch)| (ch, pos)).collect();
 
     let start = inv[&a];
     let end = inv[&b];
 
-    let mut q = VecDeque::new();
-    q.push_back((start, String::new()));
-
-    let mut accum = Vec::new();
-    let mut dist = usize::MAX;
-
-    while let Some((pos, steps)) = q.pop_front() {
-        if pos == end && steps.len() <= dist {
-            accum.push(steps.clone());
-            dist = steps.len();
-            continue;
-        }
+    let search = search::bfs(
+        start,
+        |&pos| {
+            pos.neighbours4()
+                .filter(|new_pos| coords.contains_key(new_pos))
+        },
+        |&pos| pos == end,
+    );
 
-        if (steps.len() + 1) < dist {
-            for direction in Direction::ALL {
-                let (dy, dx) = direction.to_delta();
-                let new_pos = (pos.0 + dy, pos.1 + dx);
-                if coords.contains_key(&new_pos) && coords[&new_pos] != '#' {
-                    let mut new_steps = steps.clone();
-                    new_steps.push(direction.to_char());
-                    q.push_back((new_pos, new_steps));
-                }
-            }
-        }
-    }
-
-    accum
+    search
+        .paths(&end)
+        .iter()
+        .map(|path| {
+            path.iter()
+                .tuple_windows()
+                .map(|(&from, &to)| Direction::from_offset(to - from).unwrap().to_char())
+                .collect()
+        })
+        .collect()
 }
 
 fn steps(s: &str) -> Vec<(char, char)> {
@@ -88,7 +59,8 @@ fn best_path(
 
     for path in moves.get(&(start, end)).unwrap_or(&vec!["".to_string()]) {
         if n == 0 {
-            dist = dist.min(path.len());
+            // Type the moves, then press A
+            dist = dist.min(path.len() + 1);
         } else {
             let path_length: usize = steps(&format!("A{}A", path))
                 .iter()
@@ -102,19 +74,38 @@ fn best_path(
     dist
 }
 
-fn solve(
-    input: &str,
-    n: usize,
-    door_moves: &HashMap<(char, char), Vec<String>>,
-    dpad_moves: &HashMap<(char, char), Vec<String>>,
-    memo: &mut HashMap<(char, char, usize), usize>,
-) -> usize {
-    input
-        .lines()
+/// All shortest move sequences between each pair of keys on a keypad
+fn keypad_moves(layout: &[&str]) -> HashMap<(char, char), Vec<String>> {
+    // Create coordinate map
+    let mut coords = HashMap::new();
+    for (y, line) in layout.iter().enumerate() {
+        for (x, ch) in line.chars().enumerate() {
+            if ch != '#' {
+                coords.insert(Point::new(x as isize, y as isize), ch);
+            }
+        }
+    }
+
+    // Generate all moves
+    let mut moves = HashMap::new();
+    for (a, b) in coords.values().tuple_combinations() {
+        moves.insert((*b, *a), search(&coords, *b, *a));
+        moves.insert((*a, *b), search(&coords, *a, *b));
+    }
+    moves
+}
+
+fn solve(codes: &[&str], n: usize) -> usize {
+    let door_moves = keypad_moves(&DOOR);
+    let dpad_moves = keypad_moves(&DPAD);
+    let mut memo = HashMap::new();
+
+    codes
+        .iter()
         .map(|sequence| {
             let length: usize = steps(&format!("A{}", sequence))
                 .iter()
-                .map(|&(s, e)| best_path(s, e, n, door_moves, dpad_moves, memo))
+                .map(|&(s, e)| best_path(s, e, n, &door_moves, &dpad_moves, &mut memo))
                 .sum();
 
             let numeric_string = sequence
@@ -128,44 +119,143 @@ fn solve(
         .sum::<usize>()
 }
 
-pub fn solution(input: &str) {
-    // Create coordinate maps
-    let mut door_c = HashMap::new();
-    for (y, line) in DOOR.iter().enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            if ch != '#' {
-                door_c.insert((y as isize, x as isize), ch);
+params! {
+    /// How many directional keypad robots are in each chain
+    part1_robots: usize = 2,
+    part2_robots: usize = 25,
+}
+
+pub struct Codes<'a> {
+    pub codes: Vec<&'a str>,
+}
+
+pub fn parse(input: &str) -> Result<Codes<'_>, ParseError> {
+    let p = Parser::new(input);
+    let codes = p
+        .lines()
+        .map(|line| match line.strip_suffix('A') {
+            Some(digits) if !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()) => {
+                Ok(line)
             }
-        }
+            _ => Err(p.error(line, "a door code like '029A'")),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(Codes { codes })
+}
+
+pub fn part1(codes: &Codes, params: &Params) -> Answer {
+    solve(&codes.codes, params.part1_robots).into()
+}
+
+pub fn part2(codes: &Codes, params: &Params) -> Answer {
+    solve(&codes.codes, params.part2_robots).into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+029A
+980A
+179A
+456A
+379A
+",
+    params: &[],
+    part1: Some("126384"),
+    part2: Some("154115708116294"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::gen;
+
+    fn key_at(layout: &[&str], pos: Point) -> Option<char> {
+        let row = layout.get(usize::try_from(pos.y).ok()?)?;
+        row.chars()
+            .nth(usize::try_from(pos.x).ok()?)
+            .filter(|&key| key != '#')
     }
-    let mut dpad_c = HashMap::new();
-    for (y, line) in DPAD.iter().enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            if ch != '#' {
-                dpad_c.insert((y as isize, x as isize), ch);
+
+    /// Press `key` on the keypad that moves `arms[arm]`, returning the door key that ends up
+    /// typed, if any, or an error if an arm is moved over a gap
+    fn press(arms: &mut [Point], arm: usize, key: char) -> Result<Option<char>, ()> {
+        let layout: &[&str] = if arm == 0 { &DOOR } else { &DPAD };
+        match Direction::from_char(key) {
+            Some(dir) => {
+                arms[arm] += dir.offset();
+                key_at(layout, arms[arm]).map(|_| None).ok_or(())
+            }
+            // The arm pushes the button it is aiming at
+            None => {
+                let pushed = key_at(layout, arms[arm]).unwrap();
+                match arm {
+                    0 => Ok(Some(pushed)),
+                    _ => press(arms, arm - 1, pushed),
+                }
             }
         }
     }
 
-    // Initialise moves hashmaps
-    let mut door_moves = HashMap::new();
-    let mut dpad_moves = HashMap::new();
-
-    // Generate all moves
-    for (a, b) in door_c.values().tuple_combinations() {
-        door_moves.insert((*b, *a), search(&door_c, *b, *a));
-        door_moves.insert((*a, *b), search(&door_c, *a, *b));
-    }
-    for (a, b) in dpad_c.values().tuple_combinations() {
-        dpad_moves.insert((*b, *a), search(&dpad_c, *b, *a));
-        dpad_moves.insert((*a, *b), search(&dpad_c, *a, *b));
+    // Search every sequence of human presses, tracking each arm and how much of the code is typed
+    fn presses(code: &str, robots: usize) -> usize {
+        let mut arms = vec![Point::new(2, 0); robots + 1];
+        arms[0] = Point::new(2, 3);
+        let mut seen = HashSet::from([(arms.clone(), 0)]);
+        let mut edge = vec![(arms, 0)];
+        for step in 1.. {
+            let mut next = Vec::new();
+            for (arms, typed) in edge {
+                for key in "^v<>A".chars() {
+                    let mut arms = arms.clone();
+                    let typed = match press(&mut arms, robots, key) {
+                        Err(()) => continue,
+                        Ok(None) => typed,
+                        Ok(Some(door)) if code[typed..].starts_with(door) => typed + 1,
+                        Ok(Some(_)) => continue,
+                    };
+                    if typed == code.len() {
+                        return step;
+                    }
+                    if seen.insert((arms.clone(), typed)) {
+                        next.push((arms, typed));
+                    }
+                }
+            }
+            edge = next;
+        }
+        unreachable!()
     }
 
-    let mut memo = HashMap::new();
+    fn oracle(codes: &Codes, robots: usize) -> usize {
+        codes
+            .codes
+            .iter()
+            .map(|code| presses(code, robots) * code[..code.len() - 1].parse::<usize>().unwrap())
+            .sum()
+    }
 
-    let part1 = solve(input, 2, &door_moves, &dpad_moves, &mut memo);
-    println!("Part 1: {}", part1);
+    #[test]
+    fn test_matches_oracle() {
+        let params = Params {
+            part1_robots: 1,
+            part2_robots: 2,
+        };
+        for (seed, input) in gen::samples(21, 3, 10) {
+            let codes = parse(&input).unwrap();
+            assert_eq!(part1(&codes, &params), oracle(&codes, 1).into(), "seed {}", seed);
+            assert_eq!(part2(&codes, &params), oracle(&codes, 2).into(), "seed {}", seed);
+        }
+    }
 
-    let part2 = solve(input, 25, &door_moves, &dpad_moves, &mut memo);
-    println!("Part 2: {}", part2);
+    #[test]
+    fn test_presses_a() {
+        // <A^A>^^AvvvA on the door's keypad, every move sequence ending with A, and each robot
+        // added types the presses of the one before it the same way
+        assert_eq!(solve(&["029A"], 0), 12 * 29);
+        assert_eq!(solve(&["029A"], 1), 28 * 29);
+        assert_eq!(solve(&["029A"], 2), 68 * 29);
+    }
 }
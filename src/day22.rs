@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 fn mix(secret: u64, value: u64) -> u64 {
     secret ^ value
 }
@@ -14,29 +18,45 @@ fn step(secret: u64) -> u64 {
     prune(mix(step2, step2 * 2048))
 }
 
-pub fn solution(input: &str) {
-    let part1 = input
-        .lines()
-        .map(|line| {
-            let initial = line.parse::<u64>().unwrap();
+pub struct Secrets {
+    pub initial: Vec<u64>,
+}
+
+pub fn parse(input: &str) -> Result<Secrets, ParseError> {
+    let p = Parser::new(input);
+    Ok(Secrets {
+        initial: p
+            .lines()
+            .map(|line| p.number(line))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+pub fn part1(secrets: &Secrets) -> Answer {
+    let part1 = secrets
+        .initial
+        .iter()
+        .map(|&initial| {
             // Find 2000th step value
             (0..2000).fold(initial, |secret, _| step(secret))
         })
         .sum::<u64>();
-    println!("Part 1: {}", part1);
+    part1.into()
+}
 
+pub fn part2(secrets: &Secrets) -> Answer {
     // Pre-allocate the vector with expected capacity
-    let mut maps: Vec<HashMap<[i64; 4], u64>> = Vec::with_capacity(input.lines().count());
+    let mut maps: Vec<HashMap<[i64; 4], u64>> = Vec::with_capacity(secrets.initial.len());
     // Reuse prices vector across iterations
-    let mut prices = Vec::with_capacity(2000);
+    let mut prices = Vec::with_capacity(2001);
 
-    for line in input.lines() {
-        let initial = line.parse::<u64>().unwrap();
+    for &initial in &secrets.initial {
         let mut secret = initial;
 
         prices.clear();
         prices.push(initial % 10);
-        prices.extend((0..1999).map(|_| {
+        // Then a price for each of the 2000 secrets after the initial one
+        prices.extend((0..2000).map(|_| {
             secret = step(secret);
             secret % 10
         }));
@@ -70,13 +90,108 @@ pub fn solution(input: &str) {
 
     // Find maximum sum
     let part2 = max_sums.values().max().copied().unwrap_or(0);
-
-    println!("Part 2: {}", part2);
+    part2.into()
 }
 
+pub const EXAMPLES: &[Example] = &[
+    Example {
+        input: "\
+1
+10
+100
+2024
+",
+        params: &[],
+        part1: Some("37327623"),
+        part2: None,
+    },
+    Example {
+        input: "\
+1
+2
+3
+2024
+",
+        params: &[],
+        part1: None,
+        part2: Some("23"),
+    },
+];
+
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+
     use super::*;
+    use crate::gen;
+
+    /// `initial` and the 2000 secrets after it, worked out straight from the puzzle's wording
+    fn sequence(initial: u64) -> Vec<u64> {
+        let mut secrets = vec![initial];
+        for _ in 0..2000 {
+            let mut secret = *secrets.last().unwrap();
+            secret = ((secret * 64) ^ secret) % 16777216;
+            secret = ((secret / 32) ^ secret) % 16777216;
+            secret = ((secret * 2048) ^ secret) % 16777216;
+            secrets.push(secret);
+        }
+        secrets
+    }
+
+    fn oracle1(secrets: &Secrets) -> u64 {
+        secrets.initial.iter().map(|&initial| sequence(initial)[2000]).sum()
+    }
+
+    // Try every sequence of changes that turns up in a buyer's 2001 prices, scanning each buyer
+    // for where it first appears
+    fn oracle2(secrets: &Secrets) -> u64 {
+        let buyers: Vec<(Vec<i64>, Vec<Vec<i64>>)> = secrets
+            .initial
+            .iter()
+            .map(|&initial| {
+                let prices: Vec<i64> = sequence(initial)
+                    .iter()
+                    .map(|&secret| (secret % 10) as i64)
+                    .collect();
+                let changes = prices
+                    .windows(5)
+                    .map(|w| (0..4).map(|i| w[i + 1] - w[i]).collect())
+                    .collect();
+                (prices, changes)
+            })
+            .collect();
+
+        let candidates: HashSet<&Vec<i64>> = buyers.iter().flat_map(|(_, changes)| changes).collect();
+        candidates
+            .into_iter()
+            .map(|candidate| {
+                buyers
+                    .iter()
+                    .filter_map(|(prices, changes)| {
+                        let i = changes.iter().position(|changes| changes == candidate)?;
+                        Some(prices[i + 4] as u64)
+                    })
+                    .sum()
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(22, 2, 5) {
+            let secrets = parse(&input).unwrap();
+            assert_eq!(part1(&secrets), oracle1(&secrets).into(), "seed {}", seed);
+            assert_eq!(part2(&secrets), oracle2(&secrets).into(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_last_price() {
+        // The best sequence of changes needs one buyer's 2001st and last price
+        let secrets = parse("2428653\n3671934\n5346156\n").unwrap();
+        assert_eq!(part2(&secrets), Answer::Int(27));
+    }
 
     #[test]
     fn test_mix() {
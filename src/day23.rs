@@ -2,12 +2,16 @@ use std::collections::{HashMap, HashSet};
 
 use itertools::Itertools;
 
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 fn find_triangles<'a>(graph: &'a HashMap<&str, Vec<&'a str>>) -> Vec<(&'a str, &'a str, &'a str)> {
     let mut triangles = Vec::new();
     for (a, neighbors) in graph.iter() {
         for b in neighbors {
             for c in neighbors {
-                if a < b && b < c && graph[b].contains(&c) {
+                if a < b && b < c && graph[b].contains(c) {
                     triangles.push((*a, *b, *c));
                 }
             }
@@ -98,13 +102,18 @@ fn find_maximum_clique<'a>(graph: &'a HashMap<&str, Vec<&'a str>>) -> HashSet<&'
     max_clique
 }
 
-pub fn solution(input: &str) {
-    let connections: Vec<_> = input
+pub struct Network<'a> {
+    pub graph: HashMap<&'a str, Vec<&'a str>>,
+}
+
+pub fn parse(input: &str) -> Result<Network<'_>, ParseError> {
+    let p = Parser::new(input);
+    let connections = p
         .lines()
-        .map(|line| line.split_once('-').unwrap())
-        .collect();
+        .map(|line| p.split_once(line, "-"))
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let graph: HashMap<_, _> = connections
+    let graph = connections
         .iter()
         .fold(HashMap::new(), |mut acc, (from, to)| {
             acc.entry(*from).or_insert_with(Vec::new).push(*to);
@@ -112,18 +121,122 @@ pub fn solution(input: &str) {
             acc
         });
 
-    let triangles = find_triangles(&graph);
+    Ok(Network { graph })
+}
+
+pub fn part1(network: &Network) -> Answer {
+    let triangles = find_triangles(&network.graph);
 
     let part1 = triangles
         .iter()
         .filter(|(a, b, c)| [a, b, c].iter().any(|&&node| node.starts_with("t")))
         .count();
-    println!("Part 1: {}", part1);
+    part1.into()
+}
 
-    let max_clique = find_maximum_clique(&graph);
+pub fn part2(network: &Network) -> Answer {
+    let max_clique = find_maximum_clique(&network.graph);
 
     let mut nodes: Vec<_> = max_clique.iter().collect();
     nodes.sort_unstable();
-    let part2 = nodes.iter().join(",").to_string();
-    println!("Part 2: {}", part2);
+    nodes.iter().join(",").to_string().into()
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn
+",
+    params: &[],
+    part1: Some("7"),
+    part2: Some("co,de,ka,ta"),
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    fn computers<'a>(network: &Network<'a>) -> Vec<&'a str> {
+        network.graph.keys().copied().sorted().collect()
+    }
+
+    fn linked(network: &Network, a: &str, b: &str) -> bool {
+        network.graph[a].contains(&b)
+    }
+
+    fn oracle1(network: &Network) -> usize {
+        let computers = computers(network);
+        computers
+            .iter()
+            .tuple_combinations()
+            .filter(|&(a, b, c)| {
+                linked(network, a, b) && linked(network, b, c) && linked(network, a, c)
+            })
+            .filter(|&(a, b, c)| [a, b, c].iter().any(|name| name.starts_with('t')))
+            .count()
+    }
+
+    // Every set of computers that are all linked, keeping the largest. There may be several
+    // of them, in which case any is a fair answer.
+    fn oracle2(network: &Network) -> Vec<String> {
+        let computers = computers(network);
+        let cliques: Vec<Vec<&str>> = (0..1u32 << computers.len())
+            .map(|mask| {
+                (0..computers.len())
+                    .filter(|i| mask & (1 << i) != 0)
+                    .map(|i| computers[i])
+                    .collect::<Vec<_>>()
+            })
+            .filter(|set| set.iter().tuple_combinations().all(|(a, b)| linked(network, a, b)))
+            .collect();
+        let largest = cliques.iter().map(Vec::len).max().unwrap();
+        cliques
+            .into_iter()
+            .filter(|set| set.len() == largest)
+            .map(|set| set.join(","))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(23, 14, 10) {
+            let network = parse(&input).unwrap();
+            assert_eq!(part1(&network), oracle1(&network).into(), "seed {}", seed);
+            let Answer::Str(party) = part2(&network) else {
+                panic!("seed {}", seed);
+            };
+            assert!(oracle2(&network).contains(&party), "seed {}", seed);
+        }
+    }
 }
@@ -1,25 +1,36 @@
 use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::process::Command;
 
 use itertools::Itertools;
 use rayon::iter::{ParallelBridge, ParallelIterator};
 
+use crate::answer::Answer;
+use crate::log::{self, debug, trace, warning, Level};
+use crate::params::params;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
 #[derive(Debug)]
-struct Gate<'a> {
-    logic: &'a str,
-    input1: &'a str,
-    input2: &'a str,
-    output: &'a str,
+pub struct Gate<'a> {
+    pub logic: &'a str,
+    pub input1: &'a str,
+    pub input2: &'a str,
+    pub output: &'a str,
 }
 
-fn parse_gate(line: &str) -> Option<Gate<'_>> {
-    let mut parts = line.split(' ');
-    let input1 = parts.next()?;
-    let logic = parts.next()?;
-    let input2 = parts.next()?;
-    let _ = parts.next()?;
-    let output = parts.next()?;
+/// Parse a gate like `x00 AND y00 -> z00`.
+pub fn parse_gate<'a>(p: &Parser, line: &'a str) -> Result<Gate<'a>, ParseError> {
+    let (inputs, output) = p.split_once(line, " -> ")?;
+    let [input1, logic, input2] = inputs.split(' ').collect::<Vec<_>>()[..] else {
+        return Err(p.error(inputs, "a gate like 'x00 AND y00'"));
+    };
+    if !["AND", "OR", "XOR"].contains(&logic) {
+        return Err(p.error(logic, "AND, OR or XOR"));
+    }
 
-    Some(Gate {
+    Ok(Gate {
         logic,
         input1,
         input2,
@@ -202,7 +213,7 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
 
     // Verify we have exactly one of each
     if first_and_gates.len() != 1 || first_xor_gates.len() != 1 {
-        println!(
+        debug!(
             "Invalid number of initial gates for bit {}: {} AND, {} XOR",
             bit,
             first_and_gates.len(),
@@ -227,7 +238,7 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
         .collect();
 
     if second_xor_gates.len() != 1 || second_and_gates.len() != 1 {
-        println!(
+        debug!(
             "Invalid number of secondary gates for bit {}: {} AND, {} XOR",
             bit,
             second_and_gates.len(),
@@ -245,7 +256,7 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
         .collect();
 
     if or_gates.len() != 1 {
-        println!(
+        debug!(
             "Invalid number of OR gates for bit {}: {}",
             bit,
             or_gates.len()
@@ -255,7 +266,7 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
 
     // Step 4: Verify that the second XOR produces the sum output
     if second_xor.output != sum_out {
-        println!(
+        debug!(
             "Invalid sum output for bit {}: expected {}, got {}",
             bit, sum_out, second_xor.output
         );
@@ -276,7 +287,7 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
     };
 
     if carry_in != second_and_carry {
-        println!(
+        debug!(
             "Mismatched carry inputs: XOR uses {}, AND uses {}",
             carry_in, second_and_carry
         );
@@ -286,36 +297,50 @@ fn check_full_adder(gates: &[Gate], bit: usize) -> bool {
     true
 }
 
-pub fn solution(input: &str) {
-    let (inputs, gates) = input.split_once("\n\n").unwrap();
+params! {
+    /// How many bits wide the numbers being added are
+    bits: usize = 45,
+}
+
+pub struct Device<'a> {
+    pub inputs: Vec<(&'a str, bool)>,
+    pub gates: Vec<Gate<'a>>,
+}
+
+pub fn parse(input: &str) -> Result<Device<'_>, ParseError> {
+    let p = Parser::new(input);
+    let (inputs, gates) = p.sections()?;
 
     // Parse all our gates
     let gates = gates
         .lines()
-        .map(parse_gate)
-        .collect::<Option<Vec<_>>>()
-        .unwrap();
+        .map(|line| parse_gate(&p, line))
+        .collect::<Result<Vec<_>, _>>()?;
 
     // Read the provided inputs
     let inputs = inputs
         .lines()
         .map(|line| {
-            let (input, value) = line.split_once(": ")?;
+            let (input, value) = p.split_once(line, ": ")?;
             let value = match value {
                 "0" => false,
                 "1" => true,
-                _ => return None,
+                _ => return Err(p.error(value, "0 or 1")),
             };
-            Some((input, value))
+            Ok((input, value))
         })
-        .collect::<Option<Vec<_>>>()
-        .unwrap();
+        .collect::<Result<Vec<_>, _>>()?;
 
-    let mut values: HashMap<&str, bool> = inputs.into_iter().collect();
+    Ok(Device { inputs, gates })
+}
+
+pub fn part1(device: &Device, _params: &Params) -> Answer {
+    let gates = &device.gates;
+    let mut values: HashMap<&str, bool> = device.inputs.iter().copied().collect();
 
     loop {
         let mut changed = false;
-        for gate in &gates {
+        for gate in gates {
             if let (Some(&input1), Some(&input2)) =
                 (values.get(gate.input1), values.get(gate.input2))
             {
@@ -347,48 +372,141 @@ pub fn solution(input: &str) {
             0,
             |acc, (bit, value)| if value { acc | (1 << bit) } else { acc },
         );
-    println!("Part 1: {}", part1);
-
-    // We need to find the combination of swaps that will give us the correct output.
-    // The graph forms a 44-bit adder, so we want every pair of bits to form a full adder circuit.
-    // It's easier to do this visually. We can generate a graph of each bit and its connections
-    // to the inputs, and visually inspect it to find incorrect full adder circuits.
+    part1.into()
+}
 
-    // Spit out obvious errors
-    for bit in 0..45 {
-        check_full_adder(&gates, bit);
-    }
+/// Where part 2 writes its graphs
+const GRAPH_DIR: &str = "day24-graphs";
 
-    // Create output dir
-    std::fs::create_dir_all("day24-graphs").unwrap();
+/// Write the whole circuit, and the part of it around each bit, as Graphviz files.
+fn write_graphs(gates: &[Gate], bits: usize) -> io::Result<()> {
+    fs::create_dir_all(GRAPH_DIR)?;
 
     // Generate the full graph
-    let dot = generate_dot(&gates);
-    std::fs::write("day24-graphs/full.dot", dot).unwrap();
+    let dot = generate_dot(gates);
+    fs::write(format!("{}/full.dot", GRAPH_DIR), dot)?;
 
     // Generate subgraphs for each bit
-    for bit in 0..45 {
-        println!("Generating subgraph for bit {}", bit);
-        let dot = generate_subgraph_dot(&gates, bit);
-        std::fs::write(format!("day24-graphs/bit{:02}.dot", bit), dot).unwrap();
+    for bit in 0..bits {
+        trace!("Generating subgraph for bit {}", bit);
+        let dot = generate_subgraph_dot(gates, bit);
+        fs::write(format!("{}/bit{:02}.dot", GRAPH_DIR, bit), dot)?;
     }
+    Ok(())
+}
 
+/// Render every graph file to an SVG beside it with `dot`.
+fn render_graphs() -> io::Result<()> {
     // Render all graphs in parallel
-    std::fs::read_dir("day24-graphs")
-        .unwrap()
+    fs::read_dir(GRAPH_DIR)?
         .par_bridge()
-        .for_each(|result| {
-            let entry = result.unwrap();
-            println!("Rendering graph {:?}", entry.file_name());
-            std::process::Command::new("dot")
+        .try_for_each(|entry| {
+            let path = entry?.path();
+            if path.extension().is_none_or(|extension| extension != "dot") {
+                return Ok(());
+            }
+            trace!("Rendering graph {:?}", path.file_name().unwrap_or_default());
+            let output = Command::new("dot")
                 .arg("-Tsvg")
                 .arg("-o")
-                .arg(format!(
-                    "day24-graphs/{}.svg",
-                    entry.file_name().to_string_lossy()
-                ))
-                .arg(entry.path())
-                .output()
-                .unwrap();
+                .arg(format!("{}.svg", path.display()))
+                .arg(&path)
+                .output()?;
+            if !output.status.success() {
+                return Err(io::Error::other(format!(
+                    "{}: {}",
+                    path.display(),
+                    String::from_utf8_lossy(&output.stderr).trim()
+                )));
+            }
+            Ok(())
         })
 }
+
+pub fn part2(device: &Device, params: &Params) -> Answer {
+    let gates = &device.gates;
+
+    // We need to find the combination of swaps that will give us the correct output.
+    // The graph forms a 44-bit adder, so we want every pair of bits to form a full adder circuit.
+    // It's easier to do this visually. We can generate a graph of each bit and its connections
+    // to the inputs, and visually inspect it to find incorrect full adder circuits.
+
+    // The checks and graphs are only useful when someone is looking at them
+    if !log::enabled(Level::Debug) {
+        return Answer::Unsolved;
+    }
+
+    // Spit out obvious errors
+    for bit in 0..params.bits {
+        check_full_adder(gates, bit);
+    }
+
+    if let Err(err) = write_graphs(gates, params.bits) {
+        warning!("Couldn't write the graphs to {}: {}", GRAPH_DIR, err);
+    } else if let Err(err) = render_graphs() {
+        warning!("Couldn't render the graphs with dot: {}", err);
+    }
+
+    Answer::Unsolved
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+x00: 1
+x01: 1
+x02: 1
+y00: 0
+y01: 1
+y02: 0
+
+x00 AND y00 -> z00
+x01 XOR y01 -> z01
+x02 OR y02 -> z02
+",
+    params: &[],
+    part1: Some("4"),
+    part2: None,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    /// Work out a wire by following it back through the gate that drives it
+    fn value(device: &Device, wire: &str) -> bool {
+        if let Some(&(_, value)) = device.inputs.iter().find(|(input, _)| *input == wire) {
+            return value;
+        }
+        let gate = device.gates.iter().find(|gate| gate.output == wire).unwrap();
+        let (a, b) = (value(device, gate.input1), value(device, gate.input2));
+        match gate.logic {
+            "AND" => a && b,
+            "OR" => a || b,
+            _ => a != b,
+        }
+    }
+
+    fn oracle1(device: &Device) -> u64 {
+        device
+            .gates
+            .iter()
+            .filter_map(|gate| gate.output.strip_prefix('z'))
+            .map(|bit| u64::from(value(device, &format!("z{}", bit))) << bit.parse::<u64>().unwrap())
+            .sum()
+    }
+
+    // Part 2 only draws the circuit for someone to inspect, so there's nothing to check it against
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(24, 6, 20) {
+            let device = parse(&input).unwrap();
+            assert_eq!(
+                part1(&device, &Params::default()),
+                oracle1(&device).into(),
+                "seed {}",
+                seed
+            );
+        }
+    }
+}
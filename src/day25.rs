@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+
+use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
+const WIDTH: usize = 5;
+const HEIGHT: usize = 7;
+// The rows between the solid top and bottom rows, which pins can reach into
+const SPACE: u8 = (HEIGHT - 2) as u8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Lock,
+    Key,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Schematic {
+    pub kind: Kind,
+    /// How far each pin reaches into the space between the top and bottom rows
+    pub heights: [u8; WIDTH],
+}
+
+impl Schematic {
+    fn new(p: &Parser, block: &str) -> Result<Self, ParseError> {
+        if p.grid_size(block)? != (WIDTH, HEIGHT) {
+            return Err(p.error(block, "a 5x7 schematic"));
+        }
+        let rows: Vec<&str> = block.lines().collect();
+        if let Some((i, c)) = block
+            .char_indices()
+            .find(|&(_, c)| !matches!(c, '#' | '.' | '\n'))
+        {
+            return Err(p.error(&block[i..i + c.len_utf8()], "'#' or '.'"));
+        }
+
+        let kind = match (rows[0], rows[HEIGHT - 1]) {
+            ("#####", ".....") => Kind::Lock,
+            (".....", "#####") => Kind::Key,
+            _ => return Err(p.error(rows[0], "a lock or a key")),
+        };
+
+        let mut heights = [0; WIDTH];
+        for row in &rows[1..HEIGHT - 1] {
+            for (height, c) in heights.iter_mut().zip(row.bytes()) {
+                *height += (c == b'#') as u8;
+            }
+        }
+
+        Ok(Schematic { kind, heights })
+    }
+
+    /// The cells the pins fill, five bits per column, with locks filling from the top and
+    /// keys from the bottom so a lock and key fit when their masks don't overlap.
+    pub fn mask(&self) -> u32 {
+        self.heights
+            .iter()
+            .enumerate()
+            .map(|(column, &height)| {
+                let pins = (1 << height) - 1;
+                let pins = match self.kind {
+                    Kind::Lock => pins,
+                    Kind::Key => pins << (SPACE - height),
+                };
+                pins << (column as u8 * SPACE)
+            })
+            .fold(0, |mask, pins| mask | pins)
+    }
+}
+
+pub struct Schematics {
+    pub schematics: Vec<Schematic>,
+}
+
+pub fn parse(input: &str) -> Result<Schematics, ParseError> {
+    let p = Parser::new(input);
+    let schematics = input
+        .split("\n\n")
+        .map(|block| Schematic::new(&p, block))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Schematics { schematics })
+}
+
+/// How many of each distinct mask there are of `kind`.
+fn mask_counts(schematics: &[Schematic], kind: Kind) -> HashMap<u32, usize> {
+    let mut counts = HashMap::new();
+    for schematic in schematics.iter().filter(|s| s.kind == kind) {
+        *counts.entry(schematic.mask()).or_default() += 1;
+    }
+    counts
+}
+
+pub fn part1(schematics: &Schematics) -> Answer {
+    // There are only 6^5 possible masks, so large inputs are mostly duplicates
+    let locks = mask_counts(&schematics.schematics, Kind::Lock);
+    let keys = mask_counts(&schematics.schematics, Kind::Key);
+
+    locks
+        .iter()
+        .flat_map(|(lock, lock_count)| {
+            keys.iter()
+                .filter(move |(key, _)| lock & *key == 0)
+                .map(move |(_, key_count)| lock_count * key_count)
+        })
+        .sum::<usize>()
+        .into()
+}
+
+// The last day only has one puzzle
+pub fn part2(_schematics: &Schematics) -> Answer {
+    Answer::Unsolved
+}
+
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####
+",
+    params: &[],
+    part1: Some("3"),
+    part2: None,
+}];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen;
+
+    // Overlay every lock on every key, cell by cell
+    fn oracle(input: &str) -> usize {
+        let (locks, keys): (Vec<&str>, Vec<&str>) = input
+            .split("\n\n")
+            .map(str::trim)
+            .partition(|block| block.starts_with("#####"));
+        locks
+            .iter()
+            .flat_map(|lock| keys.iter().map(move |key| (lock, key)))
+            .filter(|(lock, key)| {
+                lock.bytes()
+                    .zip(key.bytes())
+                    .all(|(a, b)| !(a == b'#' && b == b'#'))
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_matches_oracle() {
+        for (seed, input) in gen::samples(25, 30, 20) {
+            let schematics = parse(&input).unwrap();
+            assert_eq!(part1(&schematics), oracle(&input).into(), "seed {}", seed);
+        }
+    }
+
+    #[test]
+    fn test_heights() {
+        let schematics = parse(EXAMPLES[0].input).unwrap().schematics;
+        assert_eq!(schematics[0].kind, Kind::Lock);
+        assert_eq!(schematics[0].heights, [0, 5, 3, 4, 3]);
+        assert_eq!(schematics[2].kind, Kind::Key);
+        assert_eq!(schematics[2].heights, [5, 0, 2, 1, 3]);
+    }
+}
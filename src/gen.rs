@@ -0,0 +1,1004 @@
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+use std::ops::Range;
+
+use crate::day17;
+use crate::grid::Grid;
+use crate::point::{Direction, Point};
+use crate::search;
+
+/// A small pseudo-random number generator (SplitMix64), so inputs can be regenerated from a
+/// seed without a crate for it.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, each equally likely.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "can't pick from an empty range");
+        // Lemire's method, rejecting the few values that would make low results likelier
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let m = self.next_u64() as u128 * n as u128;
+            if m as u64 >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        range.start + self.below(range.end.abs_diff(range.start)) as i64
+    }
+
+    /// An index into something `len` long.
+    pub fn index(&mut self, len: usize) -> usize {
+        self.below(len as u64) as usize
+    }
+
+    /// True with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < p
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.index(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.index(i + 1));
+        }
+    }
+}
+
+/// Makes random inputs for one day.
+#[derive(Debug, Clone, Copy)]
+pub struct Generator {
+    /// What the size controls
+    pub size: &'static str,
+    /// A size about that of the real puzzle input
+    pub default_size: usize,
+    pub generate: fn(&mut Rng, usize) -> String,
+}
+
+/// Every day's generator, indexed by day - 1.
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        size: "pairs of location IDs",
+        default_size: 1000,
+        generate: day01,
+    },
+    Generator {
+        size: "reports",
+        default_size: 1000,
+        generate: day02,
+    },
+    Generator {
+        size: "instructions",
+        default_size: 700,
+        generate: day03,
+    },
+    Generator {
+        size: "width and height of the word search",
+        default_size: 140,
+        generate: day04,
+    },
+    Generator {
+        size: "updates",
+        default_size: 200,
+        generate: day05,
+    },
+    Generator {
+        size: "width and height of the lab",
+        default_size: 130,
+        generate: day06,
+    },
+    Generator {
+        size: "equations",
+        default_size: 850,
+        generate: day07,
+    },
+    Generator {
+        size: "width and height of the city",
+        default_size: 50,
+        generate: day08,
+    },
+    Generator {
+        size: "files on the disk",
+        default_size: 10000,
+        generate: day09,
+    },
+    Generator {
+        size: "width and height of the map",
+        default_size: 50,
+        generate: day10,
+    },
+    Generator {
+        size: "stones",
+        default_size: 8,
+        generate: day11,
+    },
+    Generator {
+        size: "width and height of the garden",
+        default_size: 140,
+        generate: day12,
+    },
+    Generator {
+        size: "claw machines",
+        default_size: 320,
+        generate: day13,
+    },
+    Generator {
+        size: "robots",
+        default_size: 500,
+        generate: day14,
+    },
+    Generator {
+        size: "width and height of the warehouse",
+        default_size: 50,
+        generate: day15,
+    },
+    Generator {
+        size: "width and height of the maze",
+        default_size: 141,
+        generate: day16,
+    },
+    Generator {
+        size: "octal digits in register A",
+        default_size: 14,
+        generate: day17,
+    },
+    Generator {
+        size: "falling bytes",
+        default_size: 3450,
+        generate: day18,
+    },
+    Generator {
+        size: "designs",
+        default_size: 400,
+        generate: day19,
+    },
+    Generator {
+        size: "width and height of the racetrack",
+        default_size: 141,
+        generate: day20,
+    },
+    Generator {
+        size: "door codes",
+        default_size: 5,
+        generate: day21,
+    },
+    Generator {
+        size: "buyers",
+        default_size: 2000,
+        generate: day22,
+    },
+    Generator {
+        size: "computers",
+        default_size: 520,
+        generate: day23,
+    },
+    Generator {
+        size: "bits in each number being added",
+        default_size: 45,
+        generate: day24,
+    },
+    Generator {
+        size: "schematics",
+        default_size: 500,
+        generate: day25,
+    },
+];
+
+/// A random input for `day`, `size` big (or about as big as a real one) from `seed`.
+pub fn generate(day: usize, size: Option<usize>, seed: u64) -> Result<String, String> {
+    let generator = day
+        .checked_sub(1)
+        .and_then(|i| GENERATORS.get(i))
+        .ok_or_else(|| format!("Day {} has no generator", day))?;
+    let size = size.unwrap_or(generator.default_size);
+    if size == 0 {
+        return Err("The size must be at least 1".to_string());
+    }
+    Ok((generator.generate)(&mut Rng::new(seed), size))
+}
+
+/// `count` small inputs for `day`, with the seed each came from, for testing a solver against
+/// a slow but obviously correct reference.
+#[cfg(test)]
+pub(crate) fn samples(day: usize, size: usize, count: u64) -> impl Iterator<Item = (u64, String)> {
+    (0..count).map(move |seed| (seed, generate(day, Some(size), seed).unwrap()))
+}
+
+/// A grid as puzzle text, one line per row.
+fn grid_text(grid: &Grid<char>) -> String {
+    format!("{}\n", grid)
+}
+
+/// A grid `side` square with each cell drawn from `cell`.
+fn random_grid(rng: &mut Rng, side: usize, mut cell: impl FnMut(&mut Rng) -> char) -> Grid<char> {
+    let cells = (0..side * side).map(|_| cell(rng)).collect();
+    Grid::new(side, side, cells)
+}
+
+/// A perfect maze, `side` square rounded down to odd, with walls on the border and exactly one
+/// path between any two open cells, which all have odd coordinates.
+fn maze(rng: &mut Rng, side: usize) -> Grid<char> {
+    let side = (side.max(5) - 1) / 2 * 2 + 1;
+    let mut grid = Grid::filled(side, side, '#');
+
+    // Carve with a depth first walk, backing up from dead ends
+    let start = Point::new(1, 1);
+    grid[start] = '.';
+    let mut stack = vec![start];
+    while let Some(&pos) = stack.last() {
+        let unvisited: Vec<Direction> = Direction::ALL
+            .into_iter()
+            .filter(|&d| grid.get(pos + d.offset() * 2) == Some(&'#'))
+            .collect();
+        if unvisited.is_empty() {
+            stack.pop();
+            continue;
+        }
+
+        let d = *rng.pick(&unvisited);
+        grid[pos + d] = '.';
+        grid[pos + d.offset() * 2] = '.';
+        stack.push(pos + d.offset() * 2);
+    }
+
+    grid
+}
+
+/// The corners of a maze's open cells, bottom left and top right.
+fn maze_corners(grid: &Grid<char>) -> (Point, Point) {
+    let far = grid.width() as isize - 2;
+    (Point::new(1, far), Point::new(far, 1))
+}
+
+fn day01(rng: &mut Rng, pairs: usize) -> String {
+    let left: Vec<i64> = (0..pairs).map(|_| rng.range(10000..100000)).collect();
+    let mut out = String::new();
+    for &l in &left {
+        // Reuse left IDs for some so part 2 has similarities to find
+        let r = if rng.chance(0.3) {
+            *rng.pick(&left)
+        } else {
+            rng.range(10000..100000)
+        };
+        writeln!(out, "{}   {}", l, r).unwrap();
+    }
+    out
+}
+
+fn day02(rng: &mut Rng, reports: usize) -> String {
+    let mut out = String::new();
+    for _ in 0..reports {
+        let sign = if rng.chance(0.5) { 1 } else { -1 };
+        let mut level = rng.range(30..70);
+        let mut levels: Vec<i64> = (0..rng.range(5..9))
+            .map(|_| {
+                let current = level;
+                level += sign * rng.range(1..4);
+                current
+            })
+            .collect();
+
+        // Knock a few levels out of line, sometimes more than the dampener can cope with
+        for _ in 0..rng.below(3) {
+            let i = rng.index(levels.len());
+            levels[i] += rng.range(-4..5);
+        }
+
+        let levels: Vec<String> = levels.iter().map(i64::to_string).collect();
+        writeln!(out, "{}", levels.join(" ")).unwrap();
+    }
+    out
+}
+
+fn day03(rng: &mut Rng, instructions: usize) -> String {
+    const JUNK: &[u8] = b"!@#$%^&*()[]{}<>?+-,' :;~/selecthowfromwhyundo";
+    let mut out = String::new();
+    for i in 0..instructions {
+        for _ in 0..rng.below(8) {
+            out.push(*rng.pick(JUNK) as char);
+        }
+        if i > 0 && rng.chance(0.01) {
+            out.push('\n');
+        }
+
+        let (a, b) = (rng.range(1..1000), rng.range(1..1000));
+        match rng.below(10) {
+            0 => out += "do()",
+            1 => out += "don't()",
+            // Near misses that mustn't count
+            2 => write!(out, "mul({},{}]", a, b).unwrap(),
+            3 => write!(out, "mul ( {},{} )", a, b).unwrap(),
+            _ => write!(out, "mul({},{})", a, b).unwrap(),
+        }
+    }
+    out.push('\n');
+    out
+}
+
+fn day04(rng: &mut Rng, side: usize) -> String {
+    grid_text(&random_grid(rng, side, |rng| {
+        *rng.pick(&['X', 'M', 'A', 'S'])
+    }))
+}
+
+fn day05(rng: &mut Rng, updates: usize) -> String {
+    // The rules put every pair of pages in a secret order
+    let mut pages: Vec<i64> = (10..100).collect();
+    rng.shuffle(&mut pages);
+    pages.truncate(49);
+
+    let mut rules = Vec::new();
+    for (i, before) in pages.iter().enumerate() {
+        for after in &pages[i + 1..] {
+            rules.push(format!("{}|{}", before, after));
+        }
+    }
+    rng.shuffle(&mut rules);
+
+    let mut out = rules.join("\n");
+    out += "\n\n";
+    for _ in 0..updates {
+        let len = rng.range(2..12) as usize * 2 + 1;
+        let mut picked: Vec<usize> = (0..pages.len()).collect();
+        rng.shuffle(&mut picked);
+        picked.truncate(len);
+        // About half are already in order
+        if rng.chance(0.5) {
+            picked.sort_unstable();
+        }
+
+        let update: Vec<String> = picked.iter().map(|&i| pages[i].to_string()).collect();
+        writeln!(out, "{}", update.join(",")).unwrap();
+    }
+    out
+}
+
+/// Whether a guard starting at `start` walks out of `grid` rather than round in circles.
+fn guard_leaves(grid: &Grid<char>, start: Point) -> bool {
+    let mut seen = HashSet::new();
+    let (mut pos, mut direction) = (start, Direction::Up);
+    while seen.insert((pos, direction)) {
+        match grid.get(pos + direction) {
+            None => return true,
+            Some('#') => direction = direction.turn_right(),
+            Some(_) => pos += direction,
+        }
+    }
+    false
+}
+
+fn day06(rng: &mut Rng, side: usize) -> String {
+    loop {
+        let mut grid = random_grid(rng, side, |rng| if rng.chance(0.06) { '#' } else { '.' });
+        let open: Vec<Point> = grid.find_all(&'.').collect();
+        if open.is_empty() {
+            continue;
+        }
+
+        let start = *rng.pick(&open);
+        grid[start] = '^';
+        // Part 1 needs the guard to leave
+        if guard_leaves(&grid, start) {
+            return grid_text(&grid);
+        }
+    }
+}
+
+fn day07(rng: &mut Rng, equations: usize) -> String {
+    // Keep targets small enough that nothing a solver tries on the way can overflow
+    const LIMIT: u64 = 1_000_000_000_000_000;
+
+    let mut out = String::new();
+    for _ in 0..equations {
+        let numbers: Vec<u64> = (0..rng.range(2..13))
+            .map(|_| rng.range(1..1000) as u64)
+            .collect();
+
+        // Some leave out the first number, which mustn't be skipped to make them
+        let skip = usize::from(rng.chance(0.1));
+        let mut target = numbers[skip];
+        for &n in &numbers[skip + 1..] {
+            let concat = format!("{}{}", target, n).parse::<u64>().ok();
+            let next = match rng.below(3) {
+                0 => target.checked_mul(n),
+                1 => concat,
+                _ => None,
+            };
+            target = next.filter(|&t| t < LIMIT).unwrap_or(target + n);
+        }
+        // Nudge some so they can't be made
+        if rng.chance(0.4) {
+            target += rng.range(1..10) as u64;
+        }
+
+        let numbers: Vec<String> = numbers.iter().map(u64::to_string).collect();
+        writeln!(out, "{}: {}", target, numbers.join(" ")).unwrap();
+    }
+    out
+}
+
+fn day08(rng: &mut Rng, side: usize) -> String {
+    const FREQUENCIES: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+    // Fewer frequencies on small maps, so they still get pairs
+    let frequencies = &FREQUENCIES[..side.clamp(1, FREQUENCIES.len())];
+    grid_text(&random_grid(rng, side, |rng| {
+        if rng.chance(0.07) {
+            *rng.pick(frequencies) as char
+        } else {
+            '.'
+        }
+    }))
+}
+
+fn day09(rng: &mut Rng, files: usize) -> String {
+    let mut out = String::new();
+    for i in 0..files {
+        write!(out, "{}", rng.range(1..10)).unwrap();
+        if i + 1 < files {
+            write!(out, "{}", rng.range(0..10)).unwrap();
+        }
+    }
+    out.push('\n');
+    out
+}
+
+fn day10(rng: &mut Rng, side: usize) -> String {
+    let mut grid = random_grid(rng, side, |rng| {
+        char::from_digit(rng.below(10) as u32, 10).unwrap()
+    });
+
+    // Lay trails over the noise, which cross to make forks
+    for _ in 0..(side * side / 50).max(1) {
+        let mut trail = vec![Point::new(
+            rng.index(side) as isize,
+            rng.index(side) as isize,
+        )];
+        for _ in 1..10 {
+            let last = *trail.last().unwrap();
+            let next: Vec<Point> = grid
+                .neighbours4(last)
+                .filter(|pos| !trail.contains(pos))
+                .collect();
+            if next.is_empty() {
+                break;
+            }
+            trail.push(*rng.pick(&next));
+        }
+        for (height, &pos) in trail.iter().enumerate() {
+            grid[pos] = char::from_digit(height as u32, 10).unwrap();
+        }
+    }
+
+    grid_text(&grid)
+}
+
+fn day11(rng: &mut Rng, stones: usize) -> String {
+    let stones: Vec<String> = (0..stones)
+        .map(|_| {
+            let digits = rng.range(1..8) as u32;
+            rng.range(0..10i64.pow(digits)).to_string()
+        })
+        .collect();
+    format!("{}\n", stones.join(" "))
+}
+
+fn day12(rng: &mut Rng, side: usize) -> String {
+    // Each plot takes the plant of its nearest seed, give or take, making ragged regions
+    let seeds: Vec<(Point, char)> = (0..(side * side / 40).max(1))
+        .map(|_| {
+            let pos = Point::new(rng.index(side) as isize, rng.index(side) as isize);
+            (pos, (b'A' + rng.below(26) as u8) as char)
+        })
+        .collect();
+
+    let mut grid = Grid::filled(side, side, '.');
+    for pos in grid.positions().collect::<Vec<_>>() {
+        let (_, plant) = seeds
+            .iter()
+            .min_by_key(|(seed, _)| seed.manhattan(pos) + rng.range(0..3) as isize)
+            .unwrap();
+        grid[pos] = *plant;
+    }
+    grid_text(&grid)
+}
+
+fn day13(rng: &mut Rng, machines: usize) -> String {
+    let mut out = Vec::new();
+    for _ in 0..machines {
+        // Some buttons move along the same line, which can reach a prize on it in many ways. Going
+        // diagonally keeps part 2's prizes on the line too
+        let (a, b) = if rng.chance(0.2) {
+            let step = rng.range(1..20);
+            let step = if rng.chance(0.5) {
+                (step, step)
+            } else {
+                (step, rng.range(1..20))
+            };
+            let (times_a, times_b) = (rng.range(1..6), rng.range(1..6));
+            (
+                (step.0 * times_a, step.1 * times_a),
+                (step.0 * times_b, step.1 * times_b),
+            )
+        } else {
+            (
+                (rng.range(10..100), rng.range(10..100)),
+                (rng.range(10..100), rng.range(10..100)),
+            )
+        };
+
+        // Half can be won
+        let prize = if rng.chance(0.5) {
+            let (presses_a, presses_b) = (rng.range(1..101), rng.range(1..101));
+            (
+                a.0 * presses_a + b.0 * presses_b,
+                a.1 * presses_a + b.1 * presses_b,
+            )
+        } else {
+            (rng.range(1000..20000), rng.range(1000..20000))
+        };
+
+        out.push(format!(
+            "Button A: X+{}, Y+{}\nButton B: X+{}, Y+{}\nPrize: X={}, Y={}\n",
+            a.0, a.1, b.0, b.1, prize.0, prize.1
+        ));
+    }
+    out.join("\n")
+}
+
+fn day14(rng: &mut Rng, robots: usize) -> String {
+    // The default room size, so the input runs without overrides
+    robots_in_room(rng, robots, 101, 103)
+}
+
+/// `robots` robots in a room `width` by `height`, two thirds of which gather into a tree a fifth
+/// of the room tall in the top left quadrant at some point.
+pub(crate) fn robots_in_room(rng: &mut Rng, robots: usize, width: i64, height: i64) -> String {
+    let when = rng.range(1..width * height);
+    let tree_rows = height / 5;
+    let (centre, top) = (width / 4, tree_rows * 3 / 4);
+    let mut out = String::new();
+    for i in 0..robots {
+        let target = if i < robots * 2 / 3 {
+            let row = rng.range(0..tree_rows);
+            (centre + rng.range(-row..row + 1), top + row)
+        } else {
+            (rng.range(0..width), rng.range(0..height))
+        };
+        let velocity = (rng.range(-99..100), rng.range(-99..100));
+
+        // Run the clock backwards from the tree to find where the robot starts
+        let start = (
+            (target.0 - velocity.0 * when).rem_euclid(width),
+            (target.1 - velocity.1 * when).rem_euclid(height),
+        );
+        writeln!(
+            out,
+            "p={},{} v={},{}",
+            start.0, start.1, velocity.0, velocity.1
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn day15(rng: &mut Rng, side: usize) -> String {
+    let side = side.max(3);
+    let mut grid = random_grid(rng, side, |rng| match rng.below(100) {
+        0..=4 => '#',
+        5..=29 => 'O',
+        _ => '.',
+    });
+    for pos in grid.positions().collect::<Vec<_>>() {
+        let edge = side as isize - 1;
+        if pos.x == 0 || pos.y == 0 || pos.x == edge || pos.y == edge {
+            grid[pos] = '#';
+        }
+    }
+    let inside = Point::new(
+        rng.range(1..side as i64 - 1) as isize,
+        rng.range(1..side as i64 - 1) as isize,
+    );
+    grid[inside] = '@';
+
+    let mut out = grid_text(&grid);
+    out.push('\n');
+    let moves: Vec<char> = (0..side * side * 8)
+        .map(|_| rng.pick(&Direction::ALL).to_char())
+        .collect();
+    for line in moves.chunks(1000) {
+        out.extend(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn day16(rng: &mut Rng, side: usize) -> String {
+    let mut grid = maze(rng, side);
+
+    // Knock through some walls between corridors so there are several best paths
+    let edge = grid.width() as isize - 1;
+    for pos in grid.positions().collect::<Vec<_>>() {
+        let between = (pos.x % 2 == 0) != (pos.y % 2 == 0);
+        let inside = pos.x > 0 && pos.y > 0 && pos.x < edge && pos.y < edge;
+        if between && inside && rng.chance(0.08) {
+            grid[pos] = '.';
+        }
+    }
+
+    let (start, end) = maze_corners(&grid);
+    grid[start] = 'S';
+    grid[end] = 'E';
+    grid_text(&grid)
+}
+
+fn day17(rng: &mut Rng, digits: usize) -> String {
+    // The shape every real program has: mix the low bits of A with higher ones, output, shift
+    // A three bits and loop until it runs out
+    let program = loop {
+        let mut middle = [[1, rng.below(8) as u8], [4, rng.below(8) as u8]];
+        rng.shuffle(&mut middle);
+        let program: Vec<u8> = [[2, 4], [1, rng.below(8) as u8], [7, 5]]
+            .into_iter()
+            .chain(middle)
+            .chain([[0, 3], [5, 5], [3, 0]])
+            .flatten()
+            .collect();
+        // Part 2 needs A to exist
+        if day17::quine([0, 0, 0], &program).is_some() {
+            break program;
+        }
+    };
+
+    let digits = digits.min(20) as u32;
+    let a = rng.range(8i64.pow(digits - 1)..8i64.pow(digits));
+    let program: Vec<String> = program.iter().map(u8::to_string).collect();
+    format!(
+        "Register A: {}\nRegister B: 0\nRegister C: 0\n\nProgram: {}\n",
+        a,
+        program.join(",")
+    )
+}
+
+fn day18(rng: &mut Rng, bytes: usize) -> String {
+    // The default memory size and how many bytes fall before part 1
+    const SIZE: usize = 71;
+    const FALLEN: usize = 1024;
+
+    let (start, end) = (
+        Point::ORIGIN,
+        Point::new(SIZE as isize - 1, SIZE as isize - 1),
+    );
+    let mut cells: Vec<Point> = Grid::filled(SIZE, SIZE, ())
+        .positions()
+        .filter(|&pos| pos != start && pos != end)
+        .collect();
+    let bytes = bytes.clamp(FALLEN, cells.len());
+
+    loop {
+        rng.shuffle(&mut cells);
+
+        // Part 1 needs a way through once the first bytes have fallen
+        let mut grid = Grid::filled(SIZE, SIZE, '.');
+        for &pos in &cells[..FALLEN] {
+            grid[pos] = '#';
+        }
+        let search = search::bfs(
+            start,
+            |&pos| grid.neighbours4(pos).filter(|&next| grid[next] == '.'),
+            |&pos| pos == end,
+        );
+        if search.goal_cost().is_some() {
+            return cells[..bytes]
+                .iter()
+                .map(|pos| format!("{}\n", pos))
+                .collect();
+        }
+    }
+}
+
+fn day19(rng: &mut Rng, designs: usize) -> String {
+    const COLOURS: &[u8] = b"wubrg";
+    let random_stripes = |rng: &mut Rng, len: i64| -> String {
+        (0..len).map(|_| *rng.pick(COLOURS) as char).collect()
+    };
+
+    // Leave out one colour on its own, so some designs can't be made
+    let missing = (*rng.pick(COLOURS) as char).to_string();
+    let mut patterns = HashSet::new();
+    while patterns.len() < 400 {
+        let len = rng.range(1..9);
+        let pattern = random_stripes(rng, len);
+        if pattern != missing {
+            patterns.insert(pattern);
+        }
+    }
+    let mut patterns: Vec<String> = patterns.into_iter().collect();
+    patterns.sort_unstable();
+    rng.shuffle(&mut patterns);
+
+    let mut out = patterns.join(", ");
+    out += "\n\n";
+    for _ in 0..designs {
+        let design = if rng.chance(0.5) {
+            (0..rng.range(3..10))
+                .map(|_| rng.pick(&patterns).as_str())
+                .collect()
+        } else {
+            let len = rng.range(20..60);
+            random_stripes(rng, len)
+        };
+        writeln!(out, "{}", design).unwrap();
+    }
+    out
+}
+
+fn day20(rng: &mut Rng, side: usize) -> String {
+    let maze = maze(rng, side);
+    let (start, end) = maze_corners(&maze);
+    let path = search::bfs(
+        start,
+        |&pos| maze.neighbours4(pos).filter(|&next| maze[next] == '.'),
+        |&pos| pos == end,
+    )
+    .path(&end)
+    .expect("perfect mazes connect every open cell");
+
+    // Wall off everything but the one path, so the race has a single track
+    let mut grid = maze.map(|_| '#');
+    for &pos in &path {
+        grid[pos] = '.';
+    }
+    grid[start] = 'S';
+    grid[end] = 'E';
+    grid_text(&grid)
+}
+
+fn day21(rng: &mut Rng, codes: usize) -> String {
+    (0..codes)
+        .map(|_| format!("{:03}A\n", rng.range(0..1000)))
+        .collect()
+}
+
+fn day22(rng: &mut Rng, buyers: usize) -> String {
+    (0..buyers)
+        .map(|_| format!("{}\n", rng.range(1..1 << 24)))
+        .collect()
+}
+
+fn day23(rng: &mut Rng, computers: usize) -> String {
+    let mut names: Vec<String> = (b'a'..=b'z')
+        .flat_map(|a| (b'a'..=b'z').map(move |b| format!("{}{}", a as char, b as char)))
+        .collect();
+    rng.shuffle(&mut names);
+    names.truncate(computers.max(2));
+    let n = names.len();
+
+    // A sparse random network with one big LAN party hidden in it
+    // Ordered, so the seed alone decides how the pairs come out
+    let mut edges = BTreeSet::new();
+    let p = 13.0 / n as f64;
+    for a in 0..n {
+        for b in a + 1..n {
+            if rng.chance(p) {
+                edges.insert((a, b));
+            }
+        }
+    }
+    let party = n.min(13);
+    for a in 0..party {
+        for b in a + 1..party {
+            edges.insert((a, b));
+        }
+    }
+
+    let mut lines: Vec<String> = edges
+        .into_iter()
+        .map(|(a, b)| {
+            let (a, b) = if rng.chance(0.5) { (a, b) } else { (b, a) };
+            format!("{}-{}\n", names[a], names[b])
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
+
+struct Gate {
+    logic: &'static str,
+    inputs: [String; 2],
+    output: String,
+}
+
+/// Whether every gate's inputs can be worked out before it, so the circuit settles.
+fn settles(inputs: &HashSet<String>, gates: &[Gate]) -> bool {
+    let mut known = inputs.clone();
+    let mut left: Vec<&Gate> = gates.iter().collect();
+    while !left.is_empty() {
+        let before = left.len();
+        left.retain(|gate| {
+            let ready = gate.inputs.iter().all(|input| known.contains(input));
+            if ready {
+                known.insert(gate.output.clone());
+            }
+            !ready
+        });
+        if left.len() == before {
+            return false;
+        }
+    }
+    true
+}
+
+fn day24(rng: &mut Rng, bits: usize) -> String {
+    let mut names = HashSet::new();
+    let mut wire = |rng: &mut Rng| loop {
+        let name: String = (0..3)
+            .map(|i| {
+                // Wires starting x, y or z would be mistaken for inputs and outputs
+                let letters = if i == 0 { 23 } else { 26 };
+                (b'a' + rng.below(letters) as u8) as char
+            })
+            .collect();
+        if names.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    // A ripple carry adder, with a half adder for the lowest bit
+    let mut gates = Vec::new();
+    let mut gate = |logic, a: &str, b: &str, output: String| {
+        gates.push(Gate {
+            logic,
+            inputs: [a.to_string(), b.to_string()],
+            output,
+        });
+    };
+    let mut carry = wire(rng);
+    gate("XOR", "x00", "y00", "z00".to_string());
+    gate("AND", "x00", "y00", carry.clone());
+    for bit in 1..bits {
+        let (x, y, z) = (
+            format!("x{:02}", bit),
+            format!("y{:02}", bit),
+            format!("z{:02}", bit),
+        );
+        let (sum, both, carried) = (wire(rng), wire(rng), wire(rng));
+        let next = if bit + 1 == bits {
+            format!("z{:02}", bits)
+        } else {
+            wire(rng)
+        };
+        gate("XOR", &x, &y, sum.clone());
+        gate("AND", &x, &y, both.clone());
+        gate("XOR", &sum, &carry, z);
+        gate("AND", &sum, &carry, carried.clone());
+        gate("OR", &both, &carried, next.clone());
+        carry = next;
+    }
+
+    let inputs: HashSet<String> = (0..bits)
+        .flat_map(|bit| [format!("x{:02}", bit), format!("y{:02}", bit)])
+        .collect();
+
+    // Swap four pairs of outputs, the faults part 2 is about, keeping the circuit settled
+    let swaps = (gates.len() / 2).min(4);
+    loop {
+        let mut order: Vec<usize> = (0..gates.len()).collect();
+        rng.shuffle(&mut order);
+        for pair in order[..swaps * 2].chunks(2) {
+            let output = std::mem::take(&mut gates[pair[0]].output);
+            gates[pair[0]].output = std::mem::replace(&mut gates[pair[1]].output, output);
+        }
+        if settles(&inputs, &gates) {
+            break;
+        }
+        // Undo and try other pairs
+        for pair in order[..swaps * 2].chunks(2).rev() {
+            let output = std::mem::take(&mut gates[pair[0]].output);
+            gates[pair[0]].output = std::mem::replace(&mut gates[pair[1]].output, output);
+        }
+    }
+
+    let mut out = String::new();
+    for prefix in ['x', 'y'] {
+        for bit in 0..bits {
+            writeln!(out, "{}{:02}: {}", prefix, bit, rng.below(2)).unwrap();
+        }
+    }
+    out.push('\n');
+    rng.shuffle(&mut gates);
+    for mut gate in gates {
+        rng.shuffle(&mut gate.inputs);
+        let [a, b] = &gate.inputs;
+        writeln!(out, "{} {} {} -> {}", a, gate.logic, b, gate.output).unwrap();
+    }
+    out
+}
+
+fn day25(rng: &mut Rng, schematics: usize) -> String {
+    let mut out = Vec::new();
+    for _ in 0..schematics {
+        let lock = rng.chance(0.5);
+        let heights: Vec<usize> = (0..5).map(|_| rng.index(6)).collect();
+        let rows: Vec<String> = (0..7)
+            .map(|row| {
+                heights
+                    .iter()
+                    .map(|&height| {
+                        // Locks hang from the top row and keys stand on the bottom one
+                        let filled = if lock {
+                            row <= height
+                        } else {
+                            6 - row <= height
+                        };
+                        if filled {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+        out.push(rows.join("\n") + "\n");
+    }
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SOLUTIONS;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(1);
+        assert!((0..1000).all(|_| rng.below(7) < 7));
+        assert!((0..1000).all(|_| (-3..3).contains(&rng.range(-3..3))));
+
+        let mut items: Vec<usize> = (0..50).collect();
+        rng.shuffle(&mut items);
+        assert_ne!(items, (0..50).collect::<Vec<_>>());
+        items.sort_unstable();
+        assert_eq!(items, (0..50).collect::<Vec<_>>());
+
+        assert_eq!(Rng::new(7).next_u64(), Rng::new(7).next_u64());
+    }
+
+    #[test]
+    fn test_generated_inputs_parse() {
+        for (i, generator) in GENERATORS.iter().enumerate() {
+            let day = i + 1;
+            let solution = &SOLUTIONS[i];
+            // Small inputs, but big enough for the parts with a fixed size to work
+            let size = generator.default_size.min(60);
+            for seed in 0..2 {
+                let input = generate(day, Some(size), seed).unwrap();
+                assert_eq!(input, generate(day, Some(size), seed).unwrap());
+                let parsed = (solution.parse)(&input, &solution.default_params());
+                if let Err(err) = parsed {
+                    panic!("Day {} seed {}: {}", day, seed, err.diagnostic());
+                }
+            }
+        }
+    }
+}
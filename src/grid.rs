@@ -0,0 +1,247 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+use crate::parser::{ParseError, Parser};
+use crate::point::{Point, ADJACENT, ORTHOGONAL};
+
+/// A rectangular grid of cells stored row by row, addressed by [`Point`]s from the top left.
+///
+/// Positions are signed so stepping off an edge gives a position [`Grid::get`] rejects,
+/// rather than wrapping around.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    width: usize,
+    height: usize,
+}
+
+impl<T> Grid<T> {
+    /// A grid from `cells` in row order, which must fill it exactly.
+    pub fn new(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(cells.len(), width * height, "cells don't fill the grid");
+        Grid {
+            cells,
+            width,
+            height,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(width, height, vec![value; width * height])
+    }
+
+    /// Parse `span` as a grid, turning each character into a cell with `cell`, which gives
+    /// `None` for characters that aren't `expected`.
+    pub fn parse(
+        p: &Parser,
+        span: &str,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let (width, height) = p.grid_size(span)?;
+        let mut cells = Vec::with_capacity(width * height);
+        for line in span.lines() {
+            for (i, c) in line.char_indices() {
+                let value = cell(c).ok_or_else(|| p.error(&line[i..i + c.len_utf8()], expected))?;
+                cells.push(value);
+            }
+        }
+
+        Ok(Grid::new(width, height, cells))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Where `pos` is stored in [`Grid::cells`], if it's inside the grid.
+    pub fn index(&self, pos: Point) -> Option<usize> {
+        let x = usize::try_from(pos.x).ok().filter(|&x| x < self.width)?;
+        let y = usize::try_from(pos.y).ok().filter(|&y| y < self.height)?;
+        Some(y * self.width + x)
+    }
+
+    /// The position stored at `index` in [`Grid::cells`].
+    pub fn position(&self, index: usize) -> Point {
+        // A grid with no columns has no cells, so any index is past the end of it
+        let Some(x) = index.checked_rem(self.width) else {
+            return Point::new(0, self.height as isize);
+        };
+        Point::new(x as isize, (index / self.width) as isize)
+    }
+
+    pub fn contains(&self, pos: Point) -> bool {
+        self.index(pos).is_some()
+    }
+
+    pub fn get(&self, pos: Point) -> Option<&T> {
+        self.index(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Point) -> Option<&mut T> {
+        self.index(pos).map(|i| &mut self.cells[i])
+    }
+
+    /// Every cell in row order.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    /// Every position in row order.
+    pub fn positions(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.cells.len()).map(|i| self.position(i))
+    }
+
+    /// Every position with its cell, in row order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> + '_ {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (self.position(i), cell))
+    }
+
+    /// Every row in turn, which are all empty if the grid has no columns.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
+        (0..self.height).map(|y| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> + '_ {
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// The orthogonal neighbours of `pos` inside the grid.
+    pub fn neighbours4(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(pos, &ORTHOGONAL)
+    }
+
+    /// All eight neighbours of `pos` inside the grid, including diagonals.
+    pub fn neighbours8(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(pos, &ADJACENT)
+    }
+
+    fn neighbours<'a>(
+        &'a self,
+        pos: Point,
+        offsets: &'static [Point],
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .map(move |&offset| pos + offset)
+            .filter(|&pos| self.contains(pos))
+    }
+
+    /// The first position holding `value`, in row order.
+    pub fn find(&self, value: &T) -> Option<Point>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    /// Every position holding `value`, in row order.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Point> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, cell)| *cell == value)
+            .map(|(pos, _)| pos)
+    }
+
+    /// A grid the same shape with `f` applied to every cell.
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::new(self.width, self.height, self.cells.iter().map(f).collect())
+    }
+}
+
+impl Grid<char> {
+    /// Parse `span` as a grid of whatever characters it holds.
+    pub fn parse_chars(p: &Parser, span: &str) -> Result<Self, ParseError> {
+        Grid::parse(p, span, "a character", Some)
+    }
+}
+
+/// Panics if `pos` is outside the grid, use [`Grid::get`] when it might be.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: Point) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{} is outside the grid", pos))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, pos: Point) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{} is outside the grid", pos))
+    }
+}
+
+/// One line per row, with no separators between cells.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid() {
+        let input = "ab\ncd\nef\n";
+        let grid = Grid::parse_chars(&Parser::new(input), input).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (2, 3));
+        assert_eq!(grid.get(Point::new(1, 2)), Some(&'f'));
+        assert_eq!(grid.get(Point::new(2, 0)), None);
+        assert_eq!(grid.get(Point::new(0, -1)), None);
+        assert_eq!(grid.find(&'d'), Some(Point::new(1, 1)));
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(
+            grid.neighbours4(Point::ORIGIN).collect::<Vec<_>>(),
+            [Point::new(1, 0), Point::new(0, 1)]
+        );
+        assert_eq!(grid.neighbours8(Point::ORIGIN).count(), 3);
+        assert_eq!(grid.to_string(), "ab\ncd\nef");
+    }
+
+    #[test]
+    fn test_no_columns() {
+        let grid = Grid::filled(0, 2, '.');
+        assert_eq!(grid.rows().collect::<Vec<_>>(), [&[] as &[char], &[]]);
+        assert_eq!(grid.positions().count(), 0);
+        assert_eq!(grid.position(0), Point::new(0, 2));
+        assert_eq!(grid.to_string(), "\n");
+    }
+
+    #[test]
+    fn test_parse_cells() {
+        let input = "12\n3x\n";
+        let p = Parser::new(input);
+        let err = Grid::parse(&p, input, "a digit", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!((err.line, err.column, err.width), (2, 2, 1));
+        assert_eq!(err.expected, "a digit");
+    }
+}
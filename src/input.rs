@@ -0,0 +1,63 @@
+/// Normalize puzzle input so solutions only ever see `\n` line endings.
+///
+/// Strips a byte order mark, converts CRLF (and lone CR) line endings, trims trailing
+/// whitespace from every line and collapses trailing blank lines into a single final newline.
+pub fn normalize(text: &str) -> String {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+    let mut normalized: String = text
+        .lines()
+        .flat_map(|line| [line.trim_end(), "\n"])
+        .collect();
+
+    let trimmed = normalized.trim_end_matches('\n').len();
+    normalized.truncate(trimmed);
+    if !normalized.is_empty() {
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Describe characters in normalized input that are unlikely to belong in a puzzle.
+pub fn check(text: &str) -> Vec<String> {
+    text.lines()
+        .enumerate()
+        .flat_map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .filter(|&(_, c)| !(c.is_ascii_graphic() || c == ' '))
+                .map(move |(x, c)| {
+                    format!(
+                        "line {}, column {}: unexpected character {:?} (U+{:04X})",
+                        y + 1,
+                        x + 1,
+                        c,
+                        c as u32
+                    )
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("1 2\r\n3 4  \r\n\r\n\r\n"), "1 2\n3 4\n");
+        assert_eq!(normalize("\u{feff}a\r\rb\n\n c\t"), "a\n\nb\n\n c\n");
+        assert_eq!(normalize("abc"), "abc\n");
+        assert_eq!(normalize("\n\n"), "");
+    }
+
+    #[test]
+    fn test_check() {
+        assert!(check("p=0,4 v=3,-3\n#.#\n").is_empty());
+        assert_eq!(
+            check("ab\n1\t2\n"),
+            vec!["line 2, column 2: unexpected character '\\t' (U+0009)"]
+        );
+    }
+}
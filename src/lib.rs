@@ -0,0 +1,51 @@
+//! Advent of Code 2024 solutions, along with the tools for running, checking and timing them.
+
+pub mod answer;
+pub mod bench;
+pub mod client;
+pub mod gen;
+pub mod grid;
+pub mod input;
+pub mod json;
+pub mod log;
+pub mod memory;
+pub mod params;
+pub mod parser;
+pub mod point;
+pub mod render;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod submit;
+pub mod verify;
+
+use solution::solutions;
+
+solutions!(
+    day01,
+    day02,
+    day03,
+    day04,
+    day05,
+    day06,
+    day07,
+    day08,
+    day09,
+    day10,
+    day11 with params,
+    day12,
+    day13 with params,
+    day14 with params,
+    day15,
+    day16,
+    day17,
+    day18 parses with params,
+    day19,
+    day20 with params,
+    day21 with params,
+    day22,
+    day23,
+    day24 with params,
+    day25,
+);
@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much diagnostic output to write to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Only errors (`-q`)
+    Error,
+    /// Progress and warnings, the default
+    Info,
+    /// Intermediate results such as map dumps (`-v`)
+    Debug,
+    /// Step-by-step progress inside a solution (`-vv`)
+    Trace,
+}
+
+const LEVELS: [Level; 4] = [Level::Error, Level::Info, Level::Debug, Level::Trace];
+
+// Global so diagnostics deep inside a solution don't need the level passed down to them
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Info as u8);
+
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+pub fn level() -> Level {
+    LEVELS[LEVEL.load(Ordering::Relaxed) as usize]
+}
+
+/// Whether messages at `level` should be written.
+pub fn enabled(level: Level) -> bool {
+    level <= self::level()
+}
+
+/// Write a line to stderr at [`Level::Debug`].
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Write a line to stderr at [`Level::Trace`].
+macro_rules! trace {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Trace) {
+            eprintln!($($arg)*);
+        }
+    };
+}
+
+/// Write a warning to stderr at [`Level::Info`].
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            eprintln!("Warning: {}", format_args!($($arg)*));
+        }
+    };
+}
+
+pub(crate) use {debug, trace, warning};
@@ -1,77 +1,704 @@
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-
-const SOLUTIONS: &[fn(&str)] = &[
-    day01::solution,
-    day02::solution,
-    day03::solution,
-    day04::solution,
-    day05::solution,
-    day06::solution,
-    day07::solution,
-    day08::solution,
-    day09::solution,
-    day10::solution,
-    day11::solution,
-    day12::solution,
-    day13::solution,
-    day14::solution,
-    day15::solution,
-    day16::solution,
-    day17::solution,
-    day18::solution,
-    day19::solution,
-    day20::solution,
-    day21::solution,
-    day22::solution,
-    day23::solution,
-    day24::solution,
-];
+mod args;
 
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    if args.len() != 3 {
-        eprintln!("Usage: {} <day> <input>", args[0]);
-        std::process::exit(1);
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use aoc24::answer::Answer;
+use aoc24::client::{self, Client, Fetched};
+use aoc24::gen;
+use aoc24::log::{self, Level};
+use aoc24::params::{self, AnyParams};
+use aoc24::render::{self, Frames, ImageFormat};
+use aoc24::runner::{self, Failure, Outcome, Parts};
+use aoc24::solution::{Example, Solution};
+use aoc24::submit::{self, Attempt, History, Verdict};
+use aoc24::verify::{self, Expected};
+use aoc24::{bench, input, json, scaffold, SOLUTIONS};
+use args::Args;
+
+/// How to print results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    /// One JSON object per day, one per line
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("Unknown format: {}", s)),
+        }
     }
+}
 
-    let day = match args[1].parse::<usize>() {
+fn usage(program: &str) -> ! {
+    eprintln!(
+        "Usage: {} <day> [input...] [part] [--param KEY=VALUE] [--format text|json] [--check-input] [--frames DIR]",
+        program
+    );
+    eprintln!(
+        "       {} <day> [part] --example [--param KEY=VALUE] [--frames DIR]",
+        program
+    );
+    eprintln!(
+        "       {} all [--format text|json] [--check-input]",
+        program
+    );
+    eprintln!(
+        "       {} bench <day|all> [--warmup N] [--runs N] [--save FILE] [--baseline FILE] [--threshold PERCENT] [--param KEY=VALUE] [--check-input]",
+        program
+    );
+    eprintln!(
+        "       {} verify [day|all] [--answers FILE] [--check-input]",
+        program
+    );
+    eprintln!("       {} fetch <day|all> [--base-url URL]", program);
+    eprintln!("       {} submit <day> <part> [--base-url URL]", program);
+    eprintln!("       {} new <day> [--force]", program);
+    eprintln!("       {} gen <day> [--size N] [--seed N]", program);
+    eprintln!();
+    eprintln!(
+        "--param overrides one of a day's constants, such as a grid size, and can be repeated."
+    );
+    eprintln!(
+        "--frames writes animation frames from days that draw them, as images in DIR, with \
+         --frame-format ppm|pbm and --frame-scale PIXELS_PER_CELL."
+    );
+    eprintln!(
+        "An input of - reads stdin. With several inputs, each one is run and reported in turn."
+    );
+    eprintln!("gen prints a random input for a day, as big as a real one unless --size is given.");
+    eprintln!(
+        "new adds the next day, or with --force starts an existing one over from a skeleton."
+    );
+    eprintln!(
+        "fetch and submit read the session token from $AOC_SESSION or ~/.config/aoc24/session."
+    );
+    eprintln!("Built with --features count-allocs, runs also report allocations and peak memory.");
+    eprintln!("Every command also takes -v or -vv for more diagnostics, or -q for errors only.");
+    std::process::exit(1);
+}
+
+fn fail(message: impl std::fmt::Display) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+/// Parse a command's arguments, also accepting the verbosity switches and applying them.
+fn parse_args(args: &[String], options: &[&str], switches: &[&str]) -> Args {
+    let switches = [switches, &["-q", "-v", "-vv"]].concat();
+    let args = Args::parse(args, options, &switches).unwrap_or_else(|err| fail(err));
+
+    log::set_level(if args.switch("-q") {
+        Level::Error
+    } else if args.switch("-vv") {
+        Level::Trace
+    } else if args.switch("-v") {
+        Level::Debug
+    } else {
+        Level::Info
+    });
+
+    args
+}
+
+fn parse_day(arg: &str) -> usize {
+    match arg.parse::<usize>() {
         Ok(d) if d > 0 && d <= SOLUTIONS.len() => d,
-        _ => {
-            eprintln!("Invalid day: {}", args[1]);
-            std::process::exit(1);
+        _ => fail(format!("Invalid day: {}", arg)),
+    }
+}
+
+/// Parse a `<day|all>` argument into the days it selects.
+fn parse_days(arg: &str) -> Vec<usize> {
+    if arg == "all" {
+        (1..=SOLUTIONS.len()).collect()
+    } else {
+        vec![parse_day(arg)]
+    }
+}
+
+/// The `--param key=value` overrides given on the command line.
+fn param_overrides(args: &Args) -> Vec<(&str, &str)> {
+    args.option_values("--param")
+        .iter()
+        .map(|arg| params::split_override(arg).unwrap_or_else(|err| fail(err)))
+        .collect()
+}
+
+/// Start writing animation frames if `--frames DIR` was given.
+fn set_frames(args: &Args) {
+    let Some(dir) = args.option("--frames") else {
+        return;
+    };
+    let format = args
+        .option_or("--frame-format", ImageFormat::Ppm)
+        .unwrap_or_else(|err| fail(err));
+    let scale = args
+        .option_or("--frame-scale", 4)
+        .unwrap_or_else(|err| fail(err));
+    render::set_frames(Some(Frames::new(PathBuf::from(dir), format, scale)));
+}
+
+/// A day's parameters with `overrides` applied.
+fn day_params(day: usize, overrides: &[(&str, &str)]) -> AnyParams {
+    (SOLUTIONS[day - 1].params)(overrides)
+        .unwrap_or_else(|err| fail(format!("Day {}: {}", day, err)))
+}
+
+/// Read and normalize an input file, warning about odd characters if `check` is set.
+/// A path of `-` reads stdin instead.
+///
+/// Every input goes through here so solutions never see CRLF or trailing whitespace.
+fn load_input(path: &Path, check: bool) -> std::io::Result<String> {
+    let text = if path == Path::new("-") {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(path)?
+    };
+    let input = input::normalize(&text);
+    if check && log::enabled(Level::Info) {
+        for warning in input::check(&input) {
+            eprintln!("Warning: {}: {}", path.display(), warning);
+        }
+    }
+    Ok(input)
+}
+
+/// Read a day's input from its conventional path, or explain why it was skipped.
+fn read_day_input(day: usize, check: bool) -> Result<String, String> {
+    let path = runner::input_path(day);
+    load_input(&path, check).map_err(|err| match err.kind() {
+        ErrorKind::NotFound => format!("no {}", path.display()),
+        _ => err.to_string(),
+    })
+}
+
+/// Print the full diagnostic for every day whose input failed to parse.
+fn print_diagnostics(outcomes: &[(usize, Outcome)]) {
+    for (_, outcome) in outcomes {
+        if let Outcome::Failed(Failure::Parse(err)) = outcome {
+            eprintln!("{}\n", err.diagnostic());
+        }
+    }
+}
+
+fn run_all(args: &[String]) {
+    let args = parse_args(args, &["--format"], &["--check-input"]);
+    if !args.positional.is_empty() {
+        fail("Usage: all [--format text|json] [--check-input]");
+    }
+    let check = args.switch("--check-input");
+    let format = args
+        .option_or("--format", Format::Text)
+        .unwrap_or_else(|err| fail(err));
+
+    let outcomes = runner::run_days(&parse_days("all"), |day| read_day_input(day, check));
+
+    print_diagnostics(&outcomes);
+    match format {
+        Format::Text => runner::print_summary(&outcomes),
+        Format::Json => {
+            for (day, outcome) in &outcomes {
+                println!("{}", runner::outcome_json(*day, outcome));
+            }
+        }
+    }
+}
+
+fn run_bench(args: &[String]) {
+    let args = parse_args(
+        args,
+        &[
+            "--warmup",
+            "--runs",
+            "--save",
+            "--baseline",
+            "--threshold",
+            "--param",
+        ],
+        &["--check-input"],
+    );
+
+    let [days] = args.positional.as_slice() else {
+        fail("Usage: bench <day|all> [options]");
+    };
+
+    let config = bench::Config {
+        warmup: args
+            .option_or("--warmup", 3)
+            .unwrap_or_else(|err| fail(err)),
+        runs: args.option_or("--runs", 20).unwrap_or_else(|err| fail(err)),
+    };
+    let threshold: f64 = args
+        .option_or("--threshold", 10.0)
+        .unwrap_or_else(|err| fail(err));
+
+    let baseline = args.option("--baseline").map(|path| {
+        std::fs::read_to_string(path)
+            .map_err(|err| err.to_string())
+            .and_then(|contents| json::parse(&contents))
+            .and_then(|value| bench::from_json(&value))
+            .unwrap_or_else(|err| fail(format!("Error reading baseline {}: {}", path, err)))
+    });
+
+    let overrides = param_overrides(&args);
+    if days == "all" && !overrides.is_empty() {
+        fail("--param needs a single day");
+    }
+
+    let check = args.switch("--check-input");
+    let mut results = Vec::new();
+    for day in parse_days(days) {
+        match read_day_input(day, check) {
+            Ok(input) => {
+                if log::enabled(Level::Info) {
+                    eprintln!("Benchmarking day {}...", day);
+                }
+                let params = day_params(day, &overrides);
+                match bench::bench_day(day, SOLUTIONS[day - 1].parse, &params, &input, config) {
+                    Ok(result) => results.push(result),
+                    Err(err) => eprintln!("{}\n", err.diagnostic()),
+                }
+            }
+            Err(reason) if log::enabled(Level::Info) => {
+                eprintln!("Day {}: skipped ({})", day, reason)
+            }
+            Err(_) => {}
+        }
+    }
+
+    let regressed = bench::report(&results, baseline.as_deref(), threshold / 100.0);
+
+    if let Some(path) = args.option("--save") {
+        let json = bench::to_json(&results, config).pretty();
+        if let Err(err) = std::fs::write(path, json + "\n") {
+            fail(format!("Error writing {}: {}", path, err));
+        }
+    }
+
+    if regressed {
+        std::process::exit(1);
+    }
+}
+
+fn run_verify(args: &[String]) {
+    let args = parse_args(args, &["--answers"], &["--check-input"]);
+    let check = args.switch("--check-input");
+
+    let days = match args.positional.as_slice() {
+        [] => parse_days("all"),
+        [days] => parse_days(days),
+        _ => fail("Usage: verify [day|all] [--answers FILE]"),
+    };
+
+    let path = args.option("--answers").unwrap_or("answers.toml");
+    let expected = std::fs::read_to_string(path)
+        .map_err(|err| err.to_string())
+        .and_then(|text| Expected::parse(&text))
+        .unwrap_or_else(|err| fail(format!("Error reading {}: {}", path, err)));
+
+    let outcomes: Vec<_> = runner::run_days(&days, |day| read_day_input(day, check))
+        .into_iter()
+        // Only mention missing inputs we have answers for
+        .filter(|(day, outcome)| !matches!(outcome, Outcome::Skipped(_)) || expected.has_day(*day))
+        .collect();
+
+    print_diagnostics(&outcomes);
+
+    if verify::report(&expected, &outcomes) > 0 {
+        std::process::exit(1);
+    }
+}
+
+/// A client for the site at `--base-url`, `$AOC_BASE_URL` or the real one, in that order.
+fn client(args: &Args) -> Client {
+    let base_url = args
+        .option("--base-url")
+        .map(str::to_string)
+        .or_else(|| std::env::var(client::BASE_URL_VAR).ok())
+        .unwrap_or_else(|| client::DEFAULT_BASE_URL.to_string());
+    let session = client::find_session().unwrap_or_else(|err| fail(err));
+    Client::new(&base_url, &session)
+}
+
+fn run_fetch(args: &[String]) {
+    let args = parse_args(args, &["--base-url"], &[]);
+    let [days] = args.positional.as_slice() else {
+        fail("Usage: fetch <day|all> [--base-url URL]");
+    };
+    let days = parse_days(days);
+
+    let client = client(&args);
+    let mut failed = false;
+    for day in days {
+        let path = runner::input_path(day);
+        match client::fetch(&client, day, &path) {
+            Ok(Fetched::Cached) => {
+                if log::enabled(Level::Info) {
+                    eprintln!("Day {}: already have {}", day, path.display());
+                }
+            }
+            Ok(Fetched::Downloaded) => {
+                if log::enabled(Level::Info) {
+                    eprintln!("Day {}: downloaded {}", day, path.display());
+                }
+            }
+            Err(err) => {
+                eprintln!("Day {}: {}", day, err);
+                failed = true;
+            }
         }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Solve one part of a day's input and submit the answer, unless the history shows it's pointless.
+fn run_submit(args: &[String]) {
+    let args = parse_args(args, &["--base-url"], &[]);
+    let [day, part] = args.positional.as_slice() else {
+        fail("Usage: submit <day> <part> [--base-url URL]");
+    };
+    let day = parse_day(day);
+    let parts = parse_part(part);
+    let part = if parts == Parts::Part1 { 1 } else { 2 };
+
+    let input =
+        read_day_input(day, false).unwrap_or_else(|err| fail(format!("Day {}: {}", day, err)));
+    let solution = &SOLUTIONS[day - 1];
+    let report = runner::run_day(
+        day,
+        solution.parse,
+        &solution.default_params(),
+        &input,
+        parts,
+    )
+    .unwrap_or_else(|err| fail(err.diagnostic()));
+    let (answer, _) = report.part1.or(report.part2).expect("the part was run");
+    if answer == Answer::Unsolved {
+        fail(format!("Day {} part {} has no answer to submit", day, part));
+    }
+    let answer = answer.to_string();
+
+    let path = submit::history_path();
+    let mut history = History::load(&path)
+        .unwrap_or_else(|err| fail(format!("Error reading {}: {}", path.display(), err)));
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("the clock is after 1970")
+        .as_secs();
+    if let Err(reason) = history.check(day, part, &answer, now) {
+        fail(format!("Not submitting {}: {}", answer, reason));
+    }
+
+    let page = client(&args)
+        .submit(day, part, &answer)
+        .unwrap_or_else(|err| fail(err));
+    let response = submit::parse_response(&page);
+    let attempt = Attempt {
+        day,
+        part,
+        answer: answer.clone(),
+        response,
+        time: now,
+    };
+    if let Err(err) = history.record(&path, attempt) {
+        eprintln!("Error recording attempt in {}: {}", path.display(), err);
+    }
+
+    print!(
+        "Day {} part {}: {} is {}",
+        day, part, answer, response.verdict
+    );
+    match response.wait {
+        Some(wait) => println!(", wait {}s before answering again", wait),
+        None => println!(),
+    }
+    if response.verdict != Verdict::Correct {
+        std::process::exit(1);
+    }
+}
+
+/// Generate the next day's module in this crate's source tree and register it, or with `--force`
+/// start an existing day afresh.
+fn run_new(args: &[String]) {
+    let args = parse_args(args, &[], &["--force"]);
+    let [day] = args.positional.as_slice() else {
+        fail("Usage: new <day> [--force]");
+    };
+    let day = match day.parse::<usize>() {
+        Ok(d) if (1..=25).contains(&d) => d,
+        _ => fail(format!("Invalid day: {}", day)),
+    };
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    match scaffold::new_day(root, day, SOLUTIONS.len(), args.switch("--force")) {
+        Ok(path) => println!("Created {} and registered it in src/lib.rs", path.display()),
+        Err(err) => fail(err),
+    }
+}
+
+/// Print a random input for a day, seeded from the clock unless a seed is given.
+fn run_gen(args: &[String]) {
+    let args = parse_args(args, &["--size", "--seed"], &[]);
+    let [day] = args.positional.as_slice() else {
+        fail("Usage: gen <day> [--size N] [--seed N]");
     };
+    let day = parse_day(day);
+    let size = args
+        .option("--size")
+        .map(|_| args.option_or("--size", 0).unwrap_or_else(|err| fail(err)));
+    let seed = args.option("--seed").map_or_else(
+        || {
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .expect("the clock is after 1970")
+                .as_nanos() as u64
+        },
+        |_| args.option_or("--seed", 0).unwrap_or_else(|err| fail(err)),
+    );
+
+    let input = gen::generate(day, size, seed).unwrap_or_else(|err| fail(err));
+    if log::enabled(Level::Info) {
+        let generator = &gen::GENERATORS[day - 1];
+        eprintln!(
+            "Day {}: seed {}, size {} ({})",
+            day,
+            seed,
+            size.unwrap_or(generator.default_size),
+            generator.size
+        );
+    }
+    print!("{}", input);
+}
+
+/// Print a single day's answers and timings, and allocations if they were counted, noting any
+/// answers that differ from `example`.
+///
+/// Returns false if an answer was wrong.
+fn print_report(report: &runner::Report, example: Option<&Example>) -> bool {
+    let mut correct = true;
+    let mut timings = vec![format!("parse {:.2?}", report.parse)];
+    let mut allocs: Vec<_> = report
+        .parse_allocs
+        .iter()
+        .map(|allocs| format!("parse {}", allocs))
+        .collect();
 
-    let input = match std::fs::read_to_string(&args[2]) {
-        Ok(content) => content,
-        Err(err) => {
-            eprintln!("Error reading input file: {}", err);
-            std::process::exit(1);
+    for (part, result, part_allocs) in [
+        (1, &report.part1, report.part1_allocs),
+        (2, &report.part2, report.part2_allocs),
+    ] {
+        let Some((answer, time)) = result else {
+            continue;
+        };
+        timings.push(format!("part {} {:.2?}", part, time));
+        allocs.extend(part_allocs.map(|allocs| format!("part {} {}", part, allocs)));
+
+        match example.and_then(|example| example.expected(part)) {
+            Some(expected) if answer.to_string() == expected => {
+                println!("Part {}: {} (correct)", part, answer)
+            }
+            Some(expected) => {
+                correct = false;
+                println!("Part {}: {} (expected {})", part, answer, expected);
+            }
+            None => println!("Part {}: {}", part, answer),
         }
+    }
+
+    println!("Timings: {}", timings.join(", "));
+    if !allocs.is_empty() {
+        println!("Allocations: {}", allocs.join("; "));
+    }
+    correct
+}
+
+/// Run a day on its embedded examples, only running parts with a known answer unless
+/// a part is asked for.
+///
+/// `overrides` apply on top of each example's own parameters, and since they change
+/// the answers, known answers aren't checked when there are any.
+fn run_examples(day: usize, solution: &Solution, part: Option<Parts>, overrides: &[(&str, &str)]) {
+    if solution.examples.is_empty() {
+        fail(format!("Day {} has no examples", day));
+    }
+    // Catch bad overrides before printing anything
+    day_params(day, overrides);
+
+    let mut correct = true;
+    for (i, example) in solution.examples.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("Example {}:", i + 1);
+
+        let parts = match (part, example.part1, example.part2) {
+            (Some(parts), _, _) => parts,
+            (None, Some(_), Some(_)) => Parts::Both,
+            (None, Some(_), None) => Parts::Part1,
+            (None, None, Some(_)) => Parts::Part2,
+            (None, None, None) => {
+                println!("No known answers, pass a part to run it anyway");
+                continue;
+            }
+        };
+
+        let params = day_params(day, &[example.params, overrides].concat());
+        let expected = overrides.is_empty().then_some(example);
+        match runner::run_day(day, solution.parse, &params, example.input, parts) {
+            Ok(report) => correct &= print_report(&report, expected),
+            Err(err) => {
+                correct = false;
+                eprintln!("{}", err.diagnostic());
+            }
+        }
+    }
+
+    if !correct {
+        std::process::exit(1);
+    }
+}
+
+fn parse_part(arg: &str) -> Parts {
+    match arg {
+        "1" => Parts::Part1,
+        "2" => Parts::Part2,
+        part => fail(format!("Invalid part: {}", part)),
+    }
+}
+
+fn run_one(args: &[String]) {
+    let args = parse_args(
+        args,
+        &[
+            "--format",
+            "--param",
+            "--frames",
+            "--frame-format",
+            "--frame-scale",
+        ],
+        &["--check-input", "--example"],
+    );
+    let overrides = param_overrides(&args);
+    set_frames(&args);
+    let check = args.switch("--check-input");
+    let format = args
+        .option_or("--format", Format::Text)
+        .unwrap_or_else(|err| fail(err));
+
+    if args.switch("--example") {
+        let [day, part @ ..] = args.positional.as_slice() else {
+            fail("Usage: <day> [part] --example");
+        };
+        if part.len() > 1 {
+            fail("Usage: <day> [part] --example");
+        }
+        let day = parse_day(day);
+        run_examples(
+            day,
+            &SOLUTIONS[day - 1],
+            part.first().map(|p| parse_part(p)),
+            &overrides,
+        );
+        return;
+    }
+
+    let Some((day, rest)) = args.positional.split_first() else {
+        fail("Usage: <day> [input...] [part] [--format text|json] [--check-input]");
     };
+    let day = parse_day(day);
 
-    SOLUTIONS[day - 1](&input);
+    // A trailing 1 or 2 picks the part, as long as it comes after an input
+    let (inputs, parts) = match rest {
+        [inputs @ .., part] if !inputs.is_empty() && (part == "1" || part == "2") => {
+            (inputs, parse_part(part))
+        }
+        inputs => (inputs, Parts::Both),
+    };
+    let paths: Vec<PathBuf> = if inputs.is_empty() {
+        vec![runner::input_path(day)]
+    } else {
+        inputs.iter().map(PathBuf::from).collect()
+    };
+    if paths.iter().filter(|path| *path == Path::new("-")).count() > 1 {
+        fail("stdin can only be read once");
+    }
+
+    // Only label results when there's more than one to tell apart
+    let labelled = paths.len() > 1;
+    let parse = SOLUTIONS[day - 1].parse;
+    let params = day_params(day, &overrides);
+    let mut failed = false;
+
+    for (i, path) in paths.iter().enumerate() {
+        if labelled && format == Format::Text {
+            if i > 0 {
+                println!();
+            }
+            println!("{}:", path.display());
+        }
+
+        let outcome = match load_input(path, check) {
+            Ok(input) => match runner::try_run_day(day, parse, &params, &input, parts) {
+                Ok(report) => Outcome::Ran(report),
+                Err(failure) => Outcome::Failed(failure),
+            },
+            Err(err) => Outcome::Skipped(format!(
+                "Error reading input file {}: {}",
+                path.display(),
+                err
+            )),
+        };
+        failed |= !matches!(outcome, Outcome::Ran(_));
+
+        match (&outcome, format) {
+            (Outcome::Failed(Failure::Parse(err)), _) => eprintln!("{}", err.diagnostic()),
+            (Outcome::Failed(failure), _) => eprintln!("Day {} {}", day, failure),
+            (Outcome::Skipped(reason), _) => eprintln!("{}", reason),
+            (Outcome::Ran(report), Format::Text) => {
+                print_report(report, None);
+            }
+            (Outcome::Ran(_), Format::Json) => {}
+        }
+        if format == Format::Json {
+            let mut value = runner::outcome_json(day, &outcome);
+            if let json::Value::Object(fields) = &mut value {
+                fields.push((
+                    "input".to_string(),
+                    json::Value::from(path.display().to_string()),
+                ));
+            }
+            println!("{}", value);
+        }
+    }
+
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    match args.get(1).map(String::as_str) {
+        Some("all") => run_all(&args[2..]),
+        Some("bench") => run_bench(&args[2..]),
+        Some("verify") => run_verify(&args[2..]),
+        Some("fetch") => run_fetch(&args[2..]),
+        Some("submit") => run_submit(&args[2..]),
+        Some("new") => run_new(&args[2..]),
+        Some("gen") => run_gen(&args[2..]),
+        Some(_) => run_one(&args[1..]),
+        None => usage(&args[0]),
+    }
 }
@@ -0,0 +1,175 @@
+use std::fmt;
+
+/// Whether allocations are being counted, which needs the `count-allocs` feature.
+pub const ENABLED: bool = cfg!(feature = "count-allocs");
+
+/// What a piece of code allocated while it ran.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocs {
+    /// How many allocations were made, counting each reallocation
+    pub count: u64,
+    /// The total size of every allocation
+    pub bytes: u64,
+    /// The most memory held at once, beyond what was held beforehand
+    pub peak: u64,
+}
+
+impl Allocs {
+    /// The allocations of two pieces of code run one after the other.
+    pub fn then(self, other: Allocs) -> Allocs {
+        Allocs {
+            count: self.count + other.count,
+            bytes: self.bytes + other.bytes,
+            peak: self.peak.max(other.peak),
+        }
+    }
+}
+
+impl fmt::Display for Allocs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} allocs, {}, peak {}",
+            self.count,
+            bytes(self.bytes),
+            bytes(self.peak)
+        )
+    }
+}
+
+/// Describe a size in bytes using the largest binary unit that keeps it above 1.
+pub fn bytes(n: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+    if n < 1024 {
+        return format!("{} B", n);
+    }
+    let mut size = n as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Run `f`, returning its result along with what it allocated, if allocations are counted.
+///
+/// The counts are for the whole process, so anything allocating on another thread meanwhile
+/// is counted too.
+pub fn measured<T>(f: impl FnOnce() -> T) -> (T, Option<Allocs>) {
+    #[cfg(feature = "count-allocs")]
+    {
+        let (result, allocs) = counting::measured(f);
+        (result, Some(allocs))
+    }
+    #[cfg(not(feature = "count-allocs"))]
+    {
+        (f(), None)
+    }
+}
+
+#[cfg(feature = "count-allocs")]
+mod counting {
+    use std::alloc::{GlobalAlloc, Layout, System};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use super::Allocs;
+
+    static COUNT: AtomicU64 = AtomicU64::new(0);
+    static BYTES: AtomicU64 = AtomicU64::new(0);
+    static LIVE: AtomicU64 = AtomicU64::new(0);
+    static PEAK: AtomicU64 = AtomicU64::new(0);
+
+    /// The system allocator, keeping count of what passes through it.
+    struct Counting;
+
+    #[global_allocator]
+    static ALLOCATOR: Counting = Counting;
+
+    fn allocated(size: usize) {
+        let size = size as u64;
+        COUNT.fetch_add(1, Ordering::Relaxed);
+        BYTES.fetch_add(size, Ordering::Relaxed);
+        let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+        PEAK.fetch_max(live, Ordering::Relaxed);
+    }
+
+    fn freed(size: usize) {
+        LIVE.fetch_sub(size as u64, Ordering::Relaxed);
+    }
+
+    unsafe impl GlobalAlloc for Counting {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+            let ptr = System.alloc_zeroed(layout);
+            if !ptr.is_null() {
+                allocated(layout.size());
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            System.dealloc(ptr, layout);
+            freed(layout.size());
+        }
+
+        unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+            let new_ptr = System.realloc(ptr, layout, new_size);
+            // On failure the old block is left as it was
+            if !new_ptr.is_null() {
+                freed(layout.size());
+                allocated(new_size);
+            }
+            new_ptr
+        }
+    }
+
+    pub fn measured<T>(f: impl FnOnce() -> T) -> (T, Allocs) {
+        let count = COUNT.load(Ordering::Relaxed);
+        let bytes = BYTES.load(Ordering::Relaxed);
+        let live = LIVE.load(Ordering::Relaxed);
+        // Start the peak afresh, so it only covers `f`
+        PEAK.store(live, Ordering::Relaxed);
+
+        let result = f();
+
+        let allocs = Allocs {
+            count: COUNT.load(Ordering::Relaxed) - count,
+            bytes: BYTES.load(Ordering::Relaxed) - bytes,
+            peak: PEAK.load(Ordering::Relaxed).saturating_sub(live),
+        };
+        (result, allocs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(bytes(0), "0 B");
+        assert_eq!(bytes(1023), "1023 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(5 << 30), "5.0 GiB");
+    }
+
+    #[test]
+    fn test_measured() {
+        let (v, allocs) = measured(|| vec![0u8; 4096]);
+        assert_eq!(v.len(), 4096);
+        assert_eq!(allocs.is_some(), ENABLED);
+        if let Some(allocs) = allocs {
+            assert!(allocs.count >= 1);
+            assert!(allocs.bytes >= 4096);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::any::Any;
+
+/// A day's tunable constants, with defaults for the real puzzle.
+///
+/// Days declare theirs with `params!`; days without any use `()`.
+pub trait Params: Default + Clone + Send + Sync + 'static {
+    /// Every key [`Params::set`] accepts.
+    const KEYS: &'static [&'static str];
+
+    /// Override one parameter from its command line form.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+}
+
+impl Params for () {
+    const KEYS: &'static [&'static str] = &[];
+
+    fn set(&mut self, key: &str, _value: &str) -> Result<(), String> {
+        Err(unknown(key, Self::KEYS))
+    }
+}
+
+/// The default parameters with each `key=value` override applied in turn.
+pub fn with_overrides<P: Params>(overrides: &[(&str, &str)]) -> Result<P, String> {
+    let mut params = P::default();
+    for (key, value) in overrides {
+        params.set(key, value)?;
+    }
+    Ok(params)
+}
+
+/// The error for a key a day doesn't have.
+pub fn unknown(key: &str, keys: &[&str]) -> String {
+    if keys.is_empty() {
+        format!("Unknown parameter {}, this day has none", key)
+    } else {
+        format!(
+            "Unknown parameter {}, expected one of: {}",
+            key,
+            keys.join(", ")
+        )
+    }
+}
+
+/// Split a `key=value` override.
+pub fn split_override(arg: &str) -> Result<(&str, &str), String> {
+    arg.split_once('=')
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("Expected key=value, got {}", arg))
+}
+
+/// Some day's parameters, with the type erased so every day can share a [`Solution`].
+///
+/// [`Solution`]: crate::solution::Solution
+pub struct AnyParams(Box<dyn Any + Send + Sync>);
+
+impl AnyParams {
+    pub fn new<P: Params>(params: P) -> Self {
+        AnyParams(Box::new(params))
+    }
+
+    /// The parameters, which must have been made for the day asking for them.
+    pub fn get<P: Params>(&self) -> &P {
+        self.0
+            .downcast_ref()
+            .expect("parameters belong to a different day")
+    }
+}
+
+/// Declares a day's `Params`: each field with its type and default, parsed with `FromStr`.
+macro_rules! params {
+    ($($(#[doc = $doc:expr])* $name:ident: $ty:ty = $default:expr),* $(,)?) => {
+        /// Constants tuned for the real puzzle, overridable with `--param key=value`.
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct Params {
+            $($(#[doc = $doc])* pub $name: $ty,)*
+        }
+
+        impl Default for Params {
+            fn default() -> Self {
+                Params {
+                    $($name: $default,)*
+                }
+            }
+        }
+
+        impl $crate::params::Params for Params {
+            const KEYS: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key {
+                    $(stringify!($name) => {
+                        self.$name = value
+                            .parse()
+                            .map_err(|_| format!("Invalid value for {}: {}", key, value))?;
+                    })*
+                    _ => return Err($crate::params::unknown(key, Self::KEYS)),
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use params;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    mod day {
+        super::params! {
+            /// Grid width
+            width: usize = 101,
+            height: usize = 103,
+        }
+    }
+
+    #[test]
+    fn test_overrides() {
+        let params: day::Params = with_overrides(&[("width", "11"), ("height", "7")]).unwrap();
+        assert_eq!((params.width, params.height), (11, 7));
+        assert_eq!(day::Params::default().width, 101);
+
+        assert!(with_overrides::<day::Params>(&[("width", "x")]).is_err());
+        assert_eq!(
+            with_overrides::<day::Params>(&[("depth", "1")]).unwrap_err(),
+            "Unknown parameter depth, expected one of: width, height"
+        );
+        assert!(with_overrides::<()>(&[("width", "1")]).is_err());
+        assert_eq!(split_override("a=b=c"), Ok(("a", "b=c")));
+        assert!(split_override("=1").is_err());
+    }
+}
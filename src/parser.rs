@@ -0,0 +1,202 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// An error in puzzle input, pointing at where it went wrong.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<usize>,
+    /// 1-based line number
+    pub line: usize,
+    /// 1-based column, in characters
+    pub column: usize,
+    pub expected: String,
+    /// The offending line, for diagnostics
+    pub source_line: String,
+    /// How many characters to underline
+    pub width: usize,
+}
+
+impl ParseError {
+    pub fn with_day(mut self, day: usize) -> Self {
+        self.day = Some(day);
+        self
+    }
+
+    /// A multi-line diagnostic showing the offending line.
+    pub fn diagnostic(&self) -> String {
+        let line_number = self.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let mut location = format!("line {}, column {}", self.line, self.column);
+        if let Some(day) = self.day {
+            location = format!("day {}, {}", day, location);
+        }
+
+        format!(
+            "error: expected {}\n{} --> {}\n{} |\n{} | {}\n{} | {}{}",
+            self.expected,
+            gutter,
+            location,
+            gutter,
+            line_number,
+            self.source_line,
+            gutter,
+            " ".repeat(self.column - 1),
+            "^".repeat(self.width.max(1)),
+        )
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+        write!(
+            f,
+            "line {}, column {}: expected {}",
+            self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parsing helpers that report errors relative to the whole puzzle input.
+///
+/// Every `span` passed in must be a slice of the input the parser was created with.
+#[derive(Debug, Clone, Copy)]
+pub struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    pub fn new(input: &'a str) -> Self {
+        Self { input }
+    }
+
+    /// An error at `span`.
+    pub fn error(&self, span: &str, expected: impl Into<String>) -> ParseError {
+        let start = self.input.as_ptr() as usize;
+        let offset = (span.as_ptr() as usize)
+            .checked_sub(start)
+            .filter(|&offset| offset <= self.input.len())
+            .unwrap_or(0);
+
+        let line_start = self.input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.input[offset..]
+            .find('\n')
+            .map_or(self.input.len(), |i| offset + i);
+        let source_line = &self.input[line_start..line_end];
+
+        // Only underline up to the end of the line
+        let width = span
+            .chars()
+            .take_while(|&c| c != '\n')
+            .count()
+            .min(source_line[offset - line_start..].chars().count());
+
+        ParseError {
+            day: None,
+            line: self.input[..offset].matches('\n').count() + 1,
+            column: self.input[line_start..offset].chars().count() + 1,
+            expected: expected.into(),
+            source_line: source_line.to_string(),
+            width,
+        }
+    }
+
+    /// An error just past the end of `span`.
+    pub fn error_after(&self, span: &str, expected: impl Into<String>) -> ParseError {
+        self.error(&span[span.len()..], expected)
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.input.lines()
+    }
+
+    /// Split the input into the sections before and after the first blank line.
+    pub fn sections(&self) -> Result<(&'a str, &'a str), ParseError> {
+        self.input
+            .split_once("\n\n")
+            .ok_or_else(|| self.error_after(self.input.trim_end(), "a blank line between sections"))
+    }
+
+    /// Check `span` is a non-empty grid of equal-length lines, returning its width and height.
+    pub fn grid_size(&self, span: &str) -> Result<(usize, usize), ParseError> {
+        let first = span
+            .lines()
+            .next()
+            .filter(|line| !line.is_empty())
+            .ok_or_else(|| self.error(span, "a grid"))?;
+        let width = first.chars().count();
+
+        for line in span.lines() {
+            if line.chars().count() != width {
+                return Err(self.error(line, format!("a row {} characters wide", width)));
+            }
+        }
+
+        Ok((width, span.lines().count()))
+    }
+
+    pub fn number<T: FromStr>(&self, span: &str) -> Result<T, ParseError> {
+        span.parse().map_err(|_| self.error(span, "a number"))
+    }
+
+    /// Parse whitespace-separated numbers.
+    pub fn numbers<T: FromStr>(&self, span: &str) -> Result<Vec<T>, ParseError> {
+        span.split_whitespace().map(|n| self.number(n)).collect()
+    }
+
+    /// Parse numbers separated by `separator`.
+    pub fn list<T: FromStr>(&self, span: &str, separator: &str) -> Result<Vec<T>, ParseError> {
+        span.split(separator).map(|n| self.number(n)).collect()
+    }
+
+    pub fn split_once<'b>(
+        &self,
+        span: &'b str,
+        delimiter: &str,
+    ) -> Result<(&'b str, &'b str), ParseError> {
+        span.split_once(delimiter)
+            .ok_or_else(|| self.error_after(span, format!("'{}'", delimiter)))
+    }
+
+    pub fn strip_prefix<'b>(&self, span: &'b str, prefix: &str) -> Result<&'b str, ParseError> {
+        span.strip_prefix(prefix)
+            .ok_or_else(|| self.error(span, format!("'{}'", prefix)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_position() {
+        let input = "1 2\n3 x 4\n";
+        let p = Parser::new(input);
+        let line = input.lines().nth(1).unwrap();
+        let err = p.numbers::<i64>(line).unwrap_err();
+
+        assert_eq!((err.line, err.column, err.width), (2, 3, 1));
+        assert_eq!(err.source_line, "3 x 4");
+        assert_eq!(
+            err.with_day(7).diagnostic(),
+            "error: expected a number\n  --> day 7, line 2, column 3\n  |\n2 | 3 x 4\n  |   ^"
+        );
+    }
+
+    #[test]
+    fn test_error_after() {
+        let input = "a,b\nc";
+        let p = Parser::new(input);
+        let err = p
+            .split_once(input.lines().nth(1).unwrap(), ",")
+            .unwrap_err();
+
+        assert_eq!((err.line, err.column, err.width), (2, 2, 0));
+        assert_eq!(err.expected, "','");
+        assert!(p.sections().is_err());
+    }
+}
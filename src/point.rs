@@ -0,0 +1,231 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// A position or offset on a grid, with `x` growing rightwards and `y` downwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point::new(0, 0);
+
+    pub const fn new(x: isize, y: isize) -> Self {
+        Point { x, y }
+    }
+
+    pub fn manhattan(self, other: Point) -> isize {
+        (self.x - other.x).abs() + (self.y - other.y).abs()
+    }
+
+    /// The four orthogonal neighbours, clockwise from up.
+    pub fn neighbours4(self) -> impl Iterator<Item = Point> {
+        ORTHOGONAL.into_iter().map(move |offset| self + offset)
+    }
+
+    /// All eight neighbours including diagonals, clockwise from up.
+    pub fn neighbours8(self) -> impl Iterator<Item = Point> {
+        ADJACENT.into_iter().map(move |offset| self + offset)
+    }
+}
+
+/// Offsets to the four orthogonal neighbours, clockwise from up.
+pub const ORTHOGONAL: [Point; 4] = [
+    Direction::Up.offset(),
+    Direction::Right.offset(),
+    Direction::Down.offset(),
+    Direction::Left.offset(),
+];
+
+/// Offsets to all eight neighbours, clockwise from up.
+pub const ADJACENT: [Point; 8] = [
+    Point::new(0, -1),
+    Point::new(1, -1),
+    Point::new(1, 0),
+    Point::new(1, 1),
+    Point::new(0, 1),
+    Point::new(-1, 1),
+    Point::new(-1, 0),
+    Point::new(-1, -1),
+];
+
+impl From<(isize, isize)> for Point {
+    fn from((x, y): (isize, isize)) -> Self {
+        Point { x, y }
+    }
+}
+
+/// Written `x,y`, the way puzzles give coordinates.
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{}", self.x, self.y)
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl AddAssign for Point {
+    fn add_assign(&mut self, other: Point) {
+        *self = *self + other;
+    }
+}
+
+impl SubAssign for Point {
+    fn sub_assign(&mut self, other: Point) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<isize> for Point {
+    type Output = Point;
+
+    fn mul(self, n: isize) -> Point {
+        Point::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Point {
+    type Output = Point;
+
+    fn neg(self) -> Point {
+        Point::new(-self.x, -self.y)
+    }
+}
+
+/// One of the four ways to face or move on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// Every direction, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The step taken by moving one tile this way.
+    pub const fn offset(self) -> Point {
+        match self {
+            Direction::Up => Point::new(0, -1),
+            Direction::Right => Point::new(1, 0),
+            Direction::Down => Point::new(0, 1),
+            Direction::Left => Point::new(-1, 0),
+        }
+    }
+
+    /// The direction a single step of `offset` goes in, if it's one.
+    pub fn from_offset(offset: Point) -> Option<Self> {
+        Direction::ALL.into_iter().find(|d| d.offset() == offset)
+    }
+
+    pub fn turn_right(self) -> Self {
+        Direction::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Direction::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn opposite(self) -> Self {
+        Direction::ALL[(self as usize + 2) % 4]
+    }
+
+    /// How many quarter turns it takes to face `other`, either way round.
+    pub fn turns_to(self, other: Direction) -> usize {
+        let clockwise = (other as usize + 4 - self as usize) % 4;
+        clockwise.min(4 - clockwise)
+    }
+
+    /// The arrow for a direction: `^`, `>`, `v` or `<`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl Add<Direction> for Point {
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point {
+        self + direction.offset()
+    }
+}
+
+impl AddAssign<Direction> for Point {
+    fn add_assign(&mut self, direction: Direction) {
+        *self = *self + direction;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directions() {
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_right().turn_left(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.offset(), -direction.opposite().offset());
+            assert_eq!(Direction::from_char(direction.to_char()), Some(direction));
+            assert_eq!(Direction::from_offset(direction.offset()), Some(direction));
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Up.turns_to(Direction::Left), 1);
+        assert_eq!(Direction::Up.turns_to(Direction::Down), 2);
+        assert_eq!(Direction::from_char('x'), None);
+    }
+
+    #[test]
+    fn test_point() {
+        let p = Point::new(3, -2);
+        assert_eq!(p + Direction::Up, Point::new(3, -3));
+        assert_eq!(p * 2 - Point::new(1, 1), Point::new(5, -5));
+        assert_eq!(p.manhattan(Point::ORIGIN), 5);
+        assert_eq!(p.neighbours8().count(), 8);
+        assert_eq!(p.to_string(), "3,-2");
+    }
+}
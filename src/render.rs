@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, IsTerminal, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use crate::grid::Grid;
+use crate::point::Point;
+
+/// A 24-bit colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const GREY: Rgb = Rgb(128, 128, 128);
+    pub const RED: Rgb = Rgb(220, 50, 47);
+    pub const GREEN: Rgb = Rgb(0, 200, 80);
+    pub const YELLOW: Rgb = Rgb(240, 200, 0);
+    pub const BLUE: Rgb = Rgb(40, 120, 240);
+}
+
+/// How one cell is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    /// The colour to draw the cell in, or the terminal's own colour
+    pub colour: Option<Rgb>,
+}
+
+impl Cell {
+    pub fn new(glyph: char) -> Self {
+        Cell {
+            glyph,
+            colour: None,
+        }
+    }
+
+    /// Whether the cell is empty space, which is left dark in images.
+    pub fn is_blank(&self) -> bool {
+        matches!(self.glyph, '.' | ' ')
+    }
+
+    /// The colour of the cell's pixels in an image.
+    pub fn rgb(&self) -> Rgb {
+        match self.colour {
+            Some(colour) => colour,
+            None if self.is_blank() => Rgb::BLACK,
+            None => Rgb::WHITE,
+        }
+    }
+}
+
+/// A grid of cells ready to be drawn to the terminal or an image.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Canvas {
+    pub cells: Grid<Cell>,
+}
+
+impl Canvas {
+    /// A canvas of empty `.` cells.
+    pub fn blank(width: usize, height: usize) -> Self {
+        Canvas {
+            cells: Grid::filled(width, height, Cell::new('.')),
+        }
+    }
+
+    /// Draw each cell of `grid` with `cell`.
+    pub fn from_grid<T>(grid: &Grid<T>, cell: impl FnMut(&T) -> Cell) -> Self {
+        Canvas {
+            cells: grid.map(cell),
+        }
+    }
+
+    /// Draw each cell of `grid` as its character, uncoloured.
+    pub fn from_chars(grid: &Grid<char>) -> Self {
+        Canvas::from_grid(grid, |&c| Cell::new(c))
+    }
+
+    /// Draw `text`, one line per row, as it is.
+    pub fn from_text(text: &str) -> Self {
+        let width = text.lines().map(|line| line.chars().count()).max();
+        let mut canvas = Canvas::blank(width.unwrap_or(0), text.lines().count());
+        for (y, line) in text.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                canvas.glyph(Point::new(x as isize, y as isize), c);
+            }
+        }
+        canvas
+    }
+
+    /// Override the glyph at `pos`, if it's on the canvas.
+    pub fn glyph(&mut self, pos: Point, glyph: char) {
+        if let Some(cell) = self.cells.get_mut(pos) {
+            cell.glyph = glyph;
+        }
+    }
+
+    /// Override the colour at `pos`, if it's on the canvas.
+    pub fn colour(&mut self, pos: Point, colour: Rgb) {
+        if let Some(cell) = self.cells.get_mut(pos) {
+            cell.colour = Some(colour);
+        }
+    }
+
+    /// Override the colour of every cell showing `glyph`.
+    pub fn colour_glyph(&mut self, glyph: char, colour: Rgb) {
+        let positions: Vec<Point> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.glyph == glyph)
+            .map(|(pos, _)| pos)
+            .collect();
+        for pos in positions {
+            self.colour(pos, colour);
+        }
+    }
+
+    /// The canvas as text with ANSI colour escapes, one line per row.
+    pub fn ansi(&self) -> String {
+        let mut out = String::new();
+        for (y, row) in self.cells.rows().enumerate() {
+            if y > 0 {
+                out.push('\n');
+            }
+
+            // Only switch colour where it changes, to keep the output small
+            let mut current = None;
+            for cell in row {
+                if cell.colour != current {
+                    match cell.colour {
+                        Some(Rgb(r, g, b)) => out += &format!("\x1b[38;2;{};{};{}m", r, g, b),
+                        None => out += "\x1b[0m",
+                    }
+                    current = cell.colour;
+                }
+                out.push(cell.glyph);
+            }
+            if current.is_some() {
+                out += "\x1b[0m";
+            }
+        }
+        out
+    }
+
+    /// The canvas for writing to stderr, coloured only if that's a terminal and `NO_COLOR`
+    /// isn't set.
+    pub fn for_stderr(&self) -> String {
+        if io::stderr().is_terminal() && std::env::var_os("NO_COLOR").is_none() {
+            self.ansi()
+        } else {
+            self.to_string()
+        }
+    }
+
+    /// Write the canvas as a binary PPM image, with each cell `scale` pixels square.
+    pub fn write_ppm(&self, out: &mut impl Write, scale: usize) -> io::Result<()> {
+        let (width, height) = (self.cells.width() * scale, self.cells.height() * scale);
+        write!(out, "P6\n{} {}\n255\n", width, height)?;
+
+        let mut line = Vec::with_capacity(width * 3);
+        for row in self.cells.rows() {
+            line.clear();
+            for cell in row {
+                let Rgb(r, g, b) = cell.rgb();
+                for _ in 0..scale {
+                    line.extend([r, g, b]);
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Write the canvas as a binary PBM image, with every cell that isn't blank in black and
+    /// each cell `scale` pixels square.
+    pub fn write_pbm(&self, out: &mut impl Write, scale: usize) -> io::Result<()> {
+        let (width, height) = (self.cells.width() * scale, self.cells.height() * scale);
+        write!(out, "P4\n{} {}\n", width, height)?;
+
+        // Rows are packed eight pixels to a byte, most significant first, padded to a byte
+        let mut line = vec![0u8; width.div_ceil(8)];
+        for row in self.cells.rows() {
+            line.fill(0);
+            for (x, cell) in row.iter().enumerate() {
+                if !cell.is_blank() {
+                    for px in x * scale..(x + 1) * scale {
+                        line[px / 8] |= 0x80 >> (px % 8);
+                    }
+                }
+            }
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The glyphs alone, one line per row.
+impl fmt::Display for Canvas {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.cells.map(|cell| cell.glyph))
+    }
+}
+
+/// The kind of image frames are written as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageFormat {
+    /// Full colour
+    Ppm,
+    /// Black and white
+    Pbm,
+}
+
+impl ImageFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Pbm => "pbm",
+        }
+    }
+}
+
+impl FromStr for ImageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ppm" => Ok(ImageFormat::Ppm),
+            "pbm" => Ok(ImageFormat::Pbm),
+            _ => Err(format!("Unknown image format: {}", s)),
+        }
+    }
+}
+
+/// Where animation frames go, and how they're drawn.
+#[derive(Debug, Clone)]
+pub struct Frames {
+    pub dir: PathBuf,
+    pub format: ImageFormat,
+    pub scale: usize,
+    /// How many frames have been written for each animation
+    counts: HashMap<String, usize>,
+}
+
+impl Frames {
+    pub fn new(dir: PathBuf, format: ImageFormat, scale: usize) -> Self {
+        Frames {
+            dir,
+            format,
+            scale,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Write the next frame of `animation`, returning where it went.
+    pub fn write(&mut self, animation: &str, canvas: &Canvas) -> io::Result<PathBuf> {
+        let count = self.counts.entry(animation.to_string()).or_default();
+        let path = self.dir.join(format!(
+            "{}-{:05}.{}",
+            animation,
+            count,
+            self.format.extension()
+        ));
+        *count += 1;
+
+        fs::create_dir_all(&self.dir)?;
+        let mut out = BufWriter::new(File::create(&path)?);
+        match self.format {
+            ImageFormat::Ppm => canvas.write_ppm(&mut out, self.scale)?,
+            ImageFormat::Pbm => canvas.write_pbm(&mut out, self.scale)?,
+        }
+        out.flush()?;
+        Ok(path)
+    }
+}
+
+// Global like the log level, so simulations deep inside a solution can dump frames without
+// being passed anywhere to put them
+static FRAMES: Mutex<Option<Frames>> = Mutex::new(None);
+
+/// Start (or with `None`, stop) writing animation frames.
+pub fn set_frames(frames: Option<Frames>) {
+    *FRAMES.lock().unwrap() = frames;
+}
+
+/// Whether frames are being written, so solutions can skip drawing them when they aren't.
+pub fn frames_enabled() -> bool {
+    FRAMES.lock().unwrap().is_some()
+}
+
+/// Write the next frame of `animation` if frames are being written.
+///
+/// A frame that can't be written stops any more being written, rather than failing the
+/// solution.
+pub fn frame(animation: &str, canvas: &Canvas) {
+    let mut frames = FRAMES.lock().unwrap();
+    if let Some(Err(err)) = frames.as_mut().map(|f| f.write(animation, canvas)) {
+        eprintln!("Couldn't write frame, giving up on frames: {}", err);
+        *frames = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let mut canvas = Canvas::from_text("#.\n.#\n");
+        canvas.colour(Point::new(0, 0), Rgb::RED);
+        canvas.glyph(Point::new(1, 0), 'x');
+
+        assert_eq!(canvas.to_string(), "#x\n.#");
+        assert_eq!(Canvas::from_text("").to_string(), "");
+        assert_eq!(Canvas::blank(0, 2).to_string(), "\n");
+        assert_eq!(canvas.ansi(), "\x1b[38;2;220;50;47m#\x1b[0mx\n.#");
+
+        let mut ppm = Vec::new();
+        canvas.write_ppm(&mut ppm, 1).unwrap();
+        assert_eq!(
+            ppm,
+            b"P6\n2 2\n255\n\xdc\x32\x2f\xff\xff\xff\x00\x00\x00\xff\xff\xff"
+        );
+
+        let mut pbm = Vec::new();
+        canvas.write_pbm(&mut pbm, 2).unwrap();
+        assert_eq!(pbm, b"P4\n4 4\n\xf0\xf0\x30\x30");
+    }
+}
@@ -0,0 +1,442 @@
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use rayon::prelude::*;
+
+use crate::answer::Answer;
+use crate::json::Value;
+use crate::memory::{self, Allocs};
+use crate::params::AnyParams;
+use crate::parser::ParseError;
+use crate::solution::ParseFn;
+use crate::SOLUTIONS;
+
+/// Which parts of a puzzle to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Parts {
+    Both,
+    Part1,
+    Part2,
+}
+
+impl Parts {
+    pub fn includes(self, part: u8) -> bool {
+        match self {
+            Parts::Both => true,
+            Parts::Part1 => part == 1,
+            Parts::Part2 => part == 2,
+        }
+    }
+}
+
+/// The answers and timings from running a single day.
+#[derive(Debug)]
+pub struct Report {
+    pub parse: Duration,
+    pub part1: Option<(Answer, Duration)>,
+    pub part2: Option<(Answer, Duration)>,
+    /// What parsing allocated, when built with the `count-allocs` feature
+    pub parse_allocs: Option<Allocs>,
+    /// What part 1 allocated, when it ran and allocations were counted
+    pub part1_allocs: Option<Allocs>,
+    /// What part 2 allocated, when it ran and allocations were counted
+    pub part2_allocs: Option<Allocs>,
+}
+
+impl Report {
+    pub fn part1_time(&self) -> Duration {
+        self.part1
+            .as_ref()
+            .map_or(Duration::ZERO, |(_, time)| *time)
+    }
+
+    pub fn part2_time(&self) -> Duration {
+        self.part2
+            .as_ref()
+            .map_or(Duration::ZERO, |(_, time)| *time)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.part1_time() + self.part2_time()
+    }
+
+    /// Everything the day allocated, if allocations were counted.
+    pub fn total_allocs(&self) -> Option<Allocs> {
+        [self.part1_allocs, self.part2_allocs]
+            .into_iter()
+            .flatten()
+            .fold(self.parse_allocs, |total, allocs| {
+                total.map(|total| total.then(allocs))
+            })
+    }
+}
+
+/// Where `aoc24 all` looks for a day's input.
+pub fn input_path(day: usize) -> PathBuf {
+    PathBuf::from(format!("inputs/day{:02}.txt", day))
+}
+
+/// Run `f`, returning its result along with how long it took.
+pub fn timed<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
+}
+
+pub fn run_day(
+    day: usize,
+    parse: ParseFn,
+    params: &AnyParams,
+    input: &str,
+    parts: Parts,
+) -> Result<Report, ParseError> {
+    // Counting goes outside the timing, so it isn't timed along with the solution
+    let ((solver, parse_time), parse_allocs) = memory::measured(|| timed(|| parse(input, params)));
+    let solver = solver.map_err(|err| err.with_day(day))?;
+
+    let run_part = |part: u8, solve: &dyn Fn() -> Answer| match parts
+        .includes(part)
+        .then(|| memory::measured(|| timed(solve)))
+    {
+        Some((result, allocs)) => (Some(result), allocs),
+        None => (None, None),
+    };
+    let (part1, part1_allocs) = run_part(1, &|| solver.part1());
+    let (part2, part2_allocs) = run_part(2, &|| solver.part2());
+
+    Ok(Report {
+        parse: parse_time,
+        part1,
+        part2,
+        parse_allocs,
+        part1_allocs,
+        part2_allocs,
+    })
+}
+
+/// Why a day failed to produce a report.
+#[derive(Debug)]
+pub enum Failure {
+    Parse(ParseError),
+    /// The panic message
+    Panic(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            // The day is already shown alongside the failure
+            Failure::Parse(err) => write!(
+                f,
+                "line {}, column {}: expected {}",
+                err.line, err.column, err.expected
+            ),
+            Failure::Panic(message) => write!(f, "panicked: {}", message),
+        }
+    }
+}
+
+/// Run a day like [`run_day`], turning a panic into a [`Failure`].
+pub fn try_run_day(
+    day: usize,
+    parse: ParseFn,
+    params: &AnyParams,
+    input: &str,
+    parts: Parts,
+) -> Result<Report, Failure> {
+    match panic::catch_unwind(AssertUnwindSafe(|| {
+        run_day(day, parse, params, input, parts)
+    })) {
+        Ok(result) => result.map_err(Failure::Parse),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| String::from("unknown panic"));
+            Err(Failure::Panic(message))
+        }
+    }
+}
+
+/// The result of a day in a multi-day run.
+pub enum Outcome {
+    Ran(Report),
+    Skipped(String),
+    Failed(Failure),
+}
+
+/// Run both parts of each of `days` with their default parameters, concurrently on the rayon
+/// pool, keeping them in order.
+///
+/// Allocations are counted for the whole process, so when they're counted the days run one
+/// at a time instead, to keep them apart.
+///
+/// `load` reads a day's input, or explains why the day should be skipped.
+pub fn run_days(
+    days: &[usize],
+    load: impl Fn(usize) -> Result<String, String> + Sync,
+) -> Vec<(usize, Outcome)> {
+    let run = |&day: &usize| {
+        let outcome = match load(day) {
+            Ok(input) => {
+                let solution = &SOLUTIONS[day - 1];
+                let params = solution.default_params();
+                match try_run_day(day, solution.parse, &params, &input, Parts::Both) {
+                    Ok(report) => Outcome::Ran(report),
+                    Err(failure) => Outcome::Failed(failure),
+                }
+            }
+            Err(reason) => Outcome::Skipped(reason),
+        };
+        (day, outcome)
+    };
+
+    if memory::ENABLED {
+        days.iter().map(run).collect()
+    } else {
+        days.par_iter().map(run).collect()
+    }
+}
+
+/// Describe a day's outcome as a JSON object, for `--format json`.
+///
+/// Answers are always strings so large integers survive a round trip through `f64`.
+/// Allocations only appear when they were counted.
+pub fn outcome_json(day: usize, outcome: &Outcome) -> Value {
+    let nanos = |time: Duration| Value::Number(time.as_nanos() as f64);
+    let allocs = |allocs: Allocs| {
+        Value::Object(vec![
+            ("count".to_string(), Value::Number(allocs.count as f64)),
+            ("bytes".to_string(), Value::Number(allocs.bytes as f64)),
+            ("peak".to_string(), Value::Number(allocs.peak as f64)),
+        ])
+    };
+    let part = |part: &Option<(Answer, Duration)>, part_allocs: Option<Allocs>| match part {
+        Some((answer, time)) => {
+            let mut fields = vec![
+                ("answer".to_string(), Value::from(answer.to_string())),
+                ("time_ns".to_string(), nanos(*time)),
+            ];
+            fields.extend(part_allocs.map(|a| ("allocs".to_string(), allocs(a))));
+            Value::Object(fields)
+        }
+        None => Value::Null,
+    };
+
+    let mut fields = vec![("day".to_string(), Value::Number(day as f64))];
+    match outcome {
+        Outcome::Ran(report) => {
+            fields.extend([
+                ("status".to_string(), Value::from("ok")),
+                ("parse_ns".to_string(), nanos(report.parse)),
+                (
+                    "part1".to_string(),
+                    part(&report.part1, report.part1_allocs),
+                ),
+                (
+                    "part2".to_string(),
+                    part(&report.part2, report.part2_allocs),
+                ),
+                ("total_ns".to_string(), nanos(report.total())),
+            ]);
+            if let (Some(parse), Some(total)) = (report.parse_allocs, report.total_allocs()) {
+                fields.extend([
+                    ("parse_allocs".to_string(), allocs(parse)),
+                    ("total_allocs".to_string(), allocs(total)),
+                ]);
+            }
+        }
+        Outcome::Skipped(reason) => fields.extend([
+            ("status".to_string(), Value::from("skipped")),
+            ("reason".to_string(), Value::from(reason.as_str())),
+        ]),
+        Outcome::Failed(failure) => fields.extend([
+            ("status".to_string(), Value::from("failed")),
+            ("error".to_string(), Value::from(failure.to_string())),
+        ]),
+    }
+
+    Value::Object(fields)
+}
+
+/// Print a summary table of a multi-day run, with each day's allocations when they were
+/// counted.
+pub fn print_summary(outcomes: &[(usize, Outcome)]) {
+    let mut header = vec![
+        "Day", "Part 1", "Part 2", "Parse", "Time 1", "Time 2", "Total",
+    ];
+    if memory::ENABLED {
+        header.extend(["Allocs", "Bytes", "Peak"]);
+    }
+    let alloc_cells = |allocs: Allocs| {
+        [
+            allocs.count.to_string(),
+            memory::bytes(allocs.bytes),
+            memory::bytes(allocs.peak),
+        ]
+    };
+
+    let answer = |part: &Option<(Answer, Duration)>| {
+        part.as_ref()
+            .map_or(String::from("-"), |(answer, _)| answer.to_string())
+    };
+    let time = |part: &Option<(Answer, Duration)>| {
+        part.as_ref()
+            .map_or(String::from("-"), |(_, time)| format!("{:.2?}", time))
+    };
+
+    let mut rows = vec![header.into_iter().map(String::from).collect()];
+    let mut totals = [Duration::ZERO; 4];
+    let mut total_allocs = Allocs::default();
+
+    for (day, outcome) in outcomes {
+        let row = match outcome {
+            Outcome::Ran(report) => {
+                totals[0] += report.parse;
+                totals[1] += report.part1_time();
+                totals[2] += report.part2_time();
+                totals[3] += report.total();
+
+                let mut row = vec![
+                    day.to_string(),
+                    answer(&report.part1),
+                    answer(&report.part2),
+                    format!("{:.2?}", report.parse),
+                    time(&report.part1),
+                    time(&report.part2),
+                    format!("{:.2?}", report.total()),
+                ];
+                if let Some(allocs) = report.total_allocs() {
+                    total_allocs = total_allocs.then(allocs);
+                    row.extend(alloc_cells(allocs));
+                }
+                row
+            }
+            Outcome::Skipped(reason) => vec![day.to_string(), format!("skipped ({})", reason)],
+            Outcome::Failed(error) => vec![day.to_string(), format!("FAILED ({})", error)],
+        };
+        rows.push(row);
+    }
+
+    let mut total_row = vec![
+        String::from("Total"),
+        String::new(),
+        String::new(),
+        format!("{:.2?}", totals[0]),
+        format!("{:.2?}", totals[1]),
+        format!("{:.2?}", totals[2]),
+        format!("{:.2?}", totals[3]),
+    ];
+    if memory::ENABLED {
+        total_row.extend(alloc_cells(total_allocs));
+    }
+    rows.push(total_row);
+
+    print_table(&rows, &[1, 2]);
+}
+
+/// Print rows as aligned columns, right-aligning all but the `left_aligned` columns.
+pub fn print_table(rows: &[Vec<String>], left_aligned: &[usize]) {
+    // Size each column to its widest cell
+    let mut widths = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    for row in rows {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .enumerate()
+            .map(|(i, (cell, &width))| {
+                if left_aligned.contains(&i) {
+                    format!("{:<width$}", cell)
+                } else {
+                    format!("{:>width$}", cell)
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("  ");
+        println!("{}", line.trim_end());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solver;
+
+    fn panicking<'a>(
+        input: &'a str,
+        _params: &AnyParams,
+    ) -> Result<Box<dyn Solver + 'a>, ParseError> {
+        panic!("bad input: {}", input)
+    }
+
+    #[test]
+    fn test_try_run_day_catches_panics() {
+        let params = SOLUTIONS[0].default_params();
+        let failure = try_run_day(1, panicking, &params, "x", Parts::Both).unwrap_err();
+        assert_eq!(failure.to_string(), "panicked: bad input: x");
+
+        let report =
+            try_run_day(1, SOLUTIONS[0].parse, &params, "1 4\n2 8\n", Parts::Part1).unwrap();
+        assert_eq!(report.part1.unwrap().0, Answer::Int(9));
+        assert!(report.part2.is_none());
+    }
+
+    #[test]
+    fn test_outcome_json() {
+        let report = Report {
+            parse: Duration::from_nanos(5),
+            part1: Some((Answer::Int(11), Duration::from_nanos(7))),
+            part2: None,
+            parse_allocs: None,
+            part1_allocs: None,
+            part2_allocs: None,
+        };
+        assert_eq!(
+            outcome_json(1, &Outcome::Ran(report)).to_string(),
+            r#"{"day":1,"status":"ok","parse_ns":5,"part1":{"answer":"11","time_ns":7},"part2":null,"total_ns":12}"#
+        );
+
+        let report = Report {
+            parse: Duration::from_nanos(5),
+            part1: Some((Answer::Int(11), Duration::from_nanos(7))),
+            part2: None,
+            parse_allocs: Some(Allocs {
+                count: 2,
+                bytes: 64,
+                peak: 48,
+            }),
+            part1_allocs: Some(Allocs {
+                count: 3,
+                bytes: 100,
+                peak: 40,
+            }),
+            part2_allocs: None,
+        };
+        assert_eq!(
+            outcome_json(1, &Outcome::Ran(report)).to_string(),
+            concat!(
+                r#"{"day":1,"status":"ok","parse_ns":5,"#,
+                r#""part1":{"answer":"11","time_ns":7,"allocs":{"count":3,"bytes":100,"peak":40}},"#,
+                r#""part2":null,"total_ns":12,"#,
+                r#""parse_allocs":{"count":2,"bytes":64,"peak":48},"#,
+                r#""total_allocs":{"count":5,"bytes":164,"peak":48}}"#
+            )
+        );
+
+        let failed = Outcome::Failed(Failure::Panic(String::from("oops")));
+        assert_eq!(
+            outcome_json(2, &failed).to_string(),
+            r#"{"day":2,"status":"failed","error":"panicked: oops"}"#
+        );
+    }
+}
@@ -0,0 +1,184 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The skeleton for a new day, which builds and passes its (empty) example as it stands.
+const TEMPLATE: &str = r#"use crate::answer::Answer;
+use crate::parser::{ParseError, Parser};
+use crate::solution::Example;
+
+pub struct Input<'a> {
+    pub lines: Vec<&'a str>,
+}
+
+pub fn parse(input: &str) -> Result<Input<'_>, ParseError> {
+    let p = Parser::new(input);
+    let lines = p.lines().collect();
+
+    Ok(Input { lines })
+}
+
+pub fn part1(_input: &Input) -> Answer {
+    Answer::Unsolved
+}
+
+pub fn part2(_input: &Input) -> Answer {
+    Answer::Unsolved
+}
+
+// Paste in the example from the puzzle and fill in its answers as they're given
+pub const EXAMPLES: &[Example] = &[Example {
+    input: "\
+",
+    params: &[],
+    part1: None,
+    part2: None,
+}];
+"#;
+
+pub fn day_module(day: usize) -> String {
+    format!("day{:02}", day)
+}
+
+/// Where the `solutions!` registry's entries are in `lib`, up to just before its closing `);`.
+fn registry(lib: &str) -> Result<(usize, usize), String> {
+    let start = lib
+        .find("solutions!(")
+        .ok_or("Couldn't find the solutions! registry")?;
+    let end = start
+        + lib[start..]
+            .find("\n);")
+            .ok_or("Couldn't find the end of the solutions! registry")?;
+    Ok((start, end))
+}
+
+/// Add `day` to the end of the `solutions!` registry in `lib`, the text of `lib.rs`.
+pub fn register(lib: &str, day: usize) -> Result<String, String> {
+    let (start, end) = registry(lib)?;
+
+    let module = day_module(day);
+    if lib[start..end]
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .any(|word| word == module)
+    {
+        return Err(format!("{} is already registered", module));
+    }
+
+    Ok(format!("{}\n    {},{}", &lib[..end], module, &lib[end..]))
+}
+
+/// Turn `day`'s existing entry in the `solutions!` registry back into a plain one, as the
+/// skeleton has no parameters.
+pub fn reregister(lib: &str, day: usize) -> Result<String, String> {
+    let (start, end) = registry(lib)?;
+
+    let module = day_module(day);
+    let mut offset = start;
+    for line in lib[start..end].split_inclusive('\n') {
+        let entry = line.trim().trim_end_matches(',');
+        if entry.split_whitespace().next() == Some(module.as_str()) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            let line_end = offset + line.trim_end_matches('\n').len();
+            return Ok(format!(
+                "{}{}{},{}",
+                &lib[..offset],
+                indent,
+                module,
+                &lib[line_end..]
+            ));
+        }
+        offset += line.len();
+    }
+    Err(format!("{} isn't registered", module))
+}
+
+/// Write a skeleton for `day` into the crate at `root` and register it, returning the new file.
+///
+/// Days are indexed by position, so a new `day` has to be the one after the `registered`
+/// days. One of those can be started afresh with `force`, replacing its module.
+pub fn new_day(root: &Path, day: usize, registered: usize, force: bool) -> Result<PathBuf, String> {
+    let path = root.join("src").join(format!("{}.rs", day_module(day)));
+    let lib_path = root.join("src").join("lib.rs");
+    let lib =
+        fs::read_to_string(&lib_path).map_err(|err| format!("{}: {}", lib_path.display(), err))?;
+
+    let lib = if day <= registered {
+        if !force {
+            return Err(format!(
+                "Day {} already exists, add --force to replace it with a skeleton",
+                day
+            ));
+        }
+        reregister(&lib, day)?
+    } else if day == registered + 1 {
+        if path.exists() {
+            return Err(format!("{} already exists", path.display()));
+        }
+        register(&lib, day)?
+    } else {
+        return Err(format!(
+            "The next day to add is {}, not {}",
+            registered + 1,
+            day
+        ));
+    };
+
+    fs::write(&path, TEMPLATE).map_err(|err| format!("{}: {}", path.display(), err))?;
+    fs::write(&lib_path, lib).map_err(|err| format!("{}: {}", lib_path.display(), err))?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn test_register() {
+        let lib =
+            "use solution::solutions;\n\nsolutions!(\n    day01,\n    day02 with params,\n);\n";
+        assert_eq!(
+            register(lib, 3).unwrap(),
+            "use solution::solutions;\n\nsolutions!(\n    day01,\n    day02 with params,\n    day03,\n);\n"
+        );
+        assert!(register(lib, 2).is_err());
+        assert!(register("mod day01;", 2).is_err());
+
+        assert_eq!(
+            reregister(lib, 2).unwrap(),
+            "use solution::solutions;\n\nsolutions!(\n    day01,\n    day02,\n);\n"
+        );
+        assert_eq!(reregister(lib, 1).unwrap(), lib);
+        assert_eq!(
+            reregister("solutions!(\n    day01 parses with params,\n);\n", 1).unwrap(),
+            "solutions!(\n    day01,\n);\n"
+        );
+        assert!(reregister(lib, 3).is_err());
+    }
+
+    #[test]
+    fn test_new_day() {
+        let root = env::temp_dir().join(format!("aoc24-scaffold-{}", std::process::id()));
+        let src = root.join("src");
+        fs::create_dir_all(&src).unwrap();
+        let real_lib = include_str!("lib.rs");
+
+        // Adding the next day, with the last one taken out of the registry
+        let lib = real_lib.replace("    day25,\n", "");
+        fs::write(src.join("lib.rs"), &lib).unwrap();
+        assert_eq!(new_day(&root, 25, 24, false), Ok(src.join("day25.rs")));
+        assert_eq!(fs::read_to_string(src.join("lib.rs")).unwrap(), real_lib);
+        assert_eq!(fs::read_to_string(src.join("day25.rs")).unwrap(), TEMPLATE);
+        assert!(new_day(&root, 3, 25, false).is_err());
+        assert!(new_day(&root, 27, 25, true).is_err());
+
+        // Starting a day with parameters afresh
+        fs::write(src.join("day14.rs"), "// solved").unwrap();
+        assert_eq!(new_day(&root, 14, 25, true), Ok(src.join("day14.rs")));
+        let lib = fs::read_to_string(src.join("lib.rs")).unwrap();
+        assert!(lib.contains("\n    day13 with params,\n    day14,\n    day15,\n"));
+        assert_eq!(fs::read_to_string(src.join("day14.rs")).unwrap(), TEMPLATE);
+
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,324 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// What a search found: the cheapest cost to every state it reached and how it got there.
+///
+/// Every cheapest way into a state is kept, so ties can be followed back as well as a single
+/// path.
+#[derive(Debug, Clone)]
+pub struct Search<S> {
+    pub start: S,
+    /// The cheapest cost found to each state reached
+    pub costs: HashMap<S, usize>,
+    /// Every state a step away that reaches each state at its cheapest cost
+    pub previous: HashMap<S, Vec<S>>,
+    /// The goal states reached, all at the lowest cost any goal can be reached for
+    pub goals: Vec<S>,
+}
+
+impl<S: Clone + Eq + Hash> Search<S> {
+    fn new(start: S) -> Self {
+        let costs = HashMap::from([(start.clone(), 0)]);
+        Search {
+            start,
+            costs,
+            previous: HashMap::new(),
+            goals: Vec::new(),
+        }
+    }
+
+    pub fn cost(&self, state: &S) -> Option<usize> {
+        self.costs.get(state).copied()
+    }
+
+    /// The cost of reaching a goal, if one could be.
+    pub fn goal_cost(&self) -> Option<usize> {
+        self.goals.first().and_then(|goal| self.cost(goal))
+    }
+
+    /// Record a step from `from` to `to` costing `cost` in total, returning whether it's the
+    /// cheapest way to `to` yet, and so whether `to` needs (re)visiting.
+    fn relax(&mut self, from: &S, to: S, cost: usize) -> bool {
+        if to == self.start {
+            return false;
+        }
+
+        match self.costs.get(&to) {
+            Some(&best) if cost > best => false,
+            Some(&best) if cost == best => {
+                let previous = self.previous.entry(to).or_default();
+                if !previous.contains(from) {
+                    previous.push(from.clone());
+                }
+                false
+            }
+            _ => {
+                self.costs.insert(to.clone(), cost);
+                self.previous.insert(to, vec![from.clone()]);
+                true
+            }
+        }
+    }
+
+    /// One cheapest path from the start to `to`, including both ends.
+    pub fn path(&self, to: &S) -> Option<Vec<S>> {
+        self.costs.get(to)?;
+
+        // The first way into a state was found before any later ones, so this can't loop
+        let mut path = vec![to.clone()];
+        while let Some(previous) = self.previous.get(path.last().unwrap()) {
+            path.push(previous[0].clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Every cheapest path from the start to `to`.
+    ///
+    /// Zero cost cycles give infinitely many paths, so this needs every step to cost something.
+    pub fn paths(&self, to: &S) -> Vec<Vec<S>> {
+        if *to == self.start {
+            return vec![vec![to.clone()]];
+        }
+
+        let previous = self.previous.get(to).map_or(&[][..], Vec::as_slice);
+        previous
+            .iter()
+            .flat_map(|previous| self.paths(previous))
+            .map(|mut path| {
+                path.push(to.clone());
+                path
+            })
+            .collect()
+    }
+
+    /// Every state on any cheapest path to any of the goals.
+    pub fn optimal(&self) -> HashSet<S> {
+        let mut seen: HashSet<S> = self.goals.iter().cloned().collect();
+        let mut queue: Vec<S> = self.goals.clone();
+        while let Some(state) = queue.pop() {
+            for previous in self.previous.get(&state).into_iter().flatten() {
+                if seen.insert(previous.clone()) {
+                    queue.push(previous.clone());
+                }
+            }
+        }
+        seen
+    }
+}
+
+struct Entry<S> {
+    /// Cost so far plus the heuristic's estimate of the cost to go
+    priority: usize,
+    cost: usize,
+    state: S,
+}
+
+impl<S> Ord for Entry<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.priority.cmp(&self.priority) // Note: reversed for min-heap
+    }
+}
+
+impl<S> PartialOrd for Entry<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> PartialEq for Entry<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority
+    }
+}
+
+impl<S> Eq for Entry<S> {}
+
+/// Find the cheapest way from `start` to a state satisfying `is_goal`, where `neighbours`
+/// gives each state's next states and the cost of stepping to them.
+///
+/// The search carries on until every goal as cheap as the first has been found, and if none
+/// can be reached it explores everything reachable.
+pub fn dijkstra<S, I>(
+    start: S,
+    neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(start, neighbours, |_| 0, is_goal)
+}
+
+/// [`dijkstra`] guided by `heuristic`, an estimate of the cost left to a goal.
+///
+/// The heuristic must never overestimate, or decrease by more than a step costs, for the
+/// answer to be the cheapest.
+pub fn astar<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = BinaryHeap::new();
+    queue.push(Entry {
+        priority: heuristic(&start),
+        cost: 0,
+        state: start,
+    });
+
+    while let Some(Entry {
+        priority,
+        cost,
+        state,
+    }) = queue.pop()
+    {
+        if search.goal_cost().is_some_and(|best| priority > best) {
+            break;
+        }
+        // Skip if we've found a better path since this was queued
+        if cost > search.costs[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            search.goals.push(state);
+            continue;
+        }
+
+        for (next, step) in neighbours(&state) {
+            let next_cost = cost + step;
+            if search.relax(&state, next.clone(), next_cost) {
+                queue.push(Entry {
+                    priority: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    state: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+/// [`dijkstra`] for when every step costs 0 or 1, using a deque instead of a heap.
+pub fn zero_one_bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::from([(0, start)]);
+
+    while let Some((cost, state)) = queue.pop_front() {
+        if search.goal_cost().is_some_and(|best| cost > best) {
+            break;
+        }
+        if cost > search.costs[&state] {
+            continue;
+        }
+        if is_goal(&state) {
+            search.goals.push(state);
+            continue;
+        }
+
+        for (next, step) in neighbours(&state) {
+            debug_assert!(step <= 1, "0-1 BFS steps must cost 0 or 1");
+            let next_cost = cost + step;
+            if search.relax(&state, next.clone(), next_cost) {
+                if step == 0 {
+                    queue.push_front((next_cost, next));
+                } else {
+                    queue.push_back((next_cost, next));
+                }
+            }
+        }
+    }
+
+    search
+}
+
+/// [`dijkstra`] for when every step costs 1, so the cost is the number of steps.
+pub fn bfs<S, I>(
+    start: S,
+    mut neighbours: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Search<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    zero_one_bfs(
+        start,
+        |state| neighbours(state).into_iter().map(|next| (next, 1)),
+        is_goal,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A line of numbers where each can step to the next for 1, or skip one for 3
+    fn line(n: &usize) -> Vec<(usize, usize)> {
+        vec![(n + 1, 1), (n + 2, 3)]
+    }
+
+    #[test]
+    fn test_searches_agree() {
+        let searches = [
+            dijkstra(0, line, |&n| n == 6),
+            astar(0, line, |&n| 6usize.saturating_sub(n), |&n| n == 6),
+            zero_one_bfs(
+                0,
+                |&n| {
+                    [(n + 1, 1), (n * 2, 0)]
+                        .into_iter()
+                        .filter(|&(n, _)| n < 10)
+                },
+                |&n| n == 6,
+            ),
+        ];
+        assert_eq!(searches[0].goal_cost(), Some(6));
+        assert_eq!(searches[1].goal_cost(), Some(6));
+        assert_eq!(searches[0].path(&6), Some(vec![0, 1, 2, 3, 4, 5, 6]));
+        // 0 -> 1 -> 2 -> 3 -> 6, with both doublings free
+        assert_eq!(searches[2].goal_cost(), Some(2));
+        assert_eq!(searches[2].path(&6), Some(vec![0, 1, 2, 3, 6]));
+    }
+
+    #[test]
+    fn test_all_paths() {
+        // Every path across a 3x3 grid moving only right or down
+        let search = bfs(
+            (0, 0),
+            |&(x, y)| {
+                [(x + 1, y), (x, y + 1)]
+                    .into_iter()
+                    .filter(|&(x, y)| x < 3 && y < 3)
+            },
+            |&pos| pos == (2, 2),
+        );
+        assert_eq!(search.goal_cost(), Some(4));
+        assert_eq!(search.paths(&(2, 2)).len(), 6);
+        assert_eq!(search.optimal().len(), 9);
+
+        let unreachable = bfs(
+            0,
+            |&n: &usize| [n + 1].into_iter().filter(|&n| n < 5),
+            |&n| n == 9,
+        );
+        assert_eq!(unreachable.goal_cost(), None);
+        assert_eq!(unreachable.costs.len(), 5);
+    }
+}
@@ -0,0 +1,214 @@
+use crate::answer::Answer;
+use crate::params::AnyParams;
+use crate::parser::ParseError;
+
+/// A day's parsed input, ready to solve either part.
+pub trait Solver {
+    fn part1(&self) -> Answer;
+    fn part2(&self) -> Answer;
+}
+
+/// Pairs a day's parsed input and parameters with its part functions.
+pub struct Parsed<T, P> {
+    pub input: T,
+    pub params: P,
+    pub part1: fn(&T, &P) -> Answer,
+    pub part2: fn(&T, &P) -> Answer,
+}
+
+impl<T, P> Solver for Parsed<T, P> {
+    fn part1(&self) -> Answer {
+        (self.part1)(&self.input, &self.params)
+    }
+
+    fn part2(&self) -> Answer {
+        (self.part2)(&self.input, &self.params)
+    }
+}
+
+/// Parses a day's input, returning a solver borrowing from it.
+///
+/// The parameters must come from the same day's [`ParamsFn`].
+pub type ParseFn = for<'a> fn(&'a str, &AnyParams) -> Result<Box<dyn Solver + 'a>, ParseError>;
+
+/// Applies `key=value` overrides to a day's default parameters.
+pub type ParamsFn = fn(&[(&str, &str)]) -> Result<AnyParams, String>;
+
+/// A worked example from a puzzle description, with the answers it should give.
+///
+/// Parts without a known answer are `None` and aren't checked.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    /// Overrides for parameters that only suit the real puzzle
+    pub params: &'static [(&'static str, &'static str)],
+    pub part1: Option<&'static str>,
+    pub part2: Option<&'static str>,
+}
+
+impl Example {
+    pub fn expected(&self, part: u8) -> Option<&'static str> {
+        match part {
+            1 => self.part1,
+            _ => self.part2,
+        }
+    }
+}
+
+/// Everything needed to run a day.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub parse: ParseFn,
+    pub params: ParamsFn,
+    pub examples: &'static [Example],
+}
+
+impl Solution {
+    pub fn default_params(&self) -> AnyParams {
+        (self.params)(&[]).expect("default parameters are always valid")
+    }
+}
+
+/// Run a day on each of its examples, describing every wrong answer.
+pub fn check_examples(solution: &Solution) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for (i, example) in solution.examples.iter().enumerate() {
+        let params = match (solution.params)(example.params) {
+            Ok(params) => params,
+            Err(err) => {
+                failures.push(format!("example {}: {}", i + 1, err));
+                continue;
+            }
+        };
+        let solver = match (solution.parse)(example.input, &params) {
+            Ok(solver) => solver,
+            Err(err) => {
+                failures.push(format!("example {}: {}", i + 1, err));
+                continue;
+            }
+        };
+
+        for part in [1, 2] {
+            let Some(expected) = example.expected(part) else {
+                continue;
+            };
+            let answer = match part {
+                1 => solver.part1(),
+                _ => solver.part2(),
+            };
+            if answer.to_string() != expected {
+                failures.push(format!(
+                    "example {} part {}: got {}, expected {}",
+                    i + 1,
+                    part,
+                    answer,
+                    expected
+                ));
+            }
+        }
+    }
+
+    failures
+}
+
+/// Builds a [`Solution`] from a day module exposing `parse`, `part1`, `part2` and `EXAMPLES`.
+///
+/// Days listed as `dayNN with params` also declare a `Params` struct with
+/// `params!`, which both parts take as a second argument. Those listed as
+/// `dayNN parses with params` need them to parse the input too.
+macro_rules! solution {
+    ($day:ident) => {{
+        fn parse<'a>(
+            input: &'a str,
+            _params: &$crate::params::AnyParams,
+        ) -> Result<Box<dyn $crate::solution::Solver + 'a>, $crate::parser::ParseError> {
+            Ok(Box::new($crate::solution::Parsed {
+                input: $day::parse(input)?,
+                params: (),
+                part1: |input, _| $day::part1(input),
+                part2: |input, _| $day::part2(input),
+            }))
+        }
+        $crate::solution::Solution {
+            parse,
+            params: |overrides| {
+                $crate::params::with_overrides::<()>(overrides).map($crate::params::AnyParams::new)
+            },
+            examples: $day::EXAMPLES,
+        }
+    }};
+    ($day:ident with params) => {{
+        fn parse<'a>(
+            input: &'a str,
+            params: &$crate::params::AnyParams,
+        ) -> Result<Box<dyn $crate::solution::Solver + 'a>, $crate::parser::ParseError> {
+            Ok(Box::new($crate::solution::Parsed {
+                input: $day::parse(input)?,
+                params: params.get::<$day::Params>().clone(),
+                part1: $day::part1,
+                part2: $day::part2,
+            }))
+        }
+        $crate::solution::Solution {
+            parse,
+            params: |overrides| {
+                $crate::params::with_overrides::<$day::Params>(overrides)
+                    .map($crate::params::AnyParams::new)
+            },
+            examples: $day::EXAMPLES,
+        }
+    }};
+    ($day:ident parses with params) => {{
+        fn parse<'a>(
+            input: &'a str,
+            params: &$crate::params::AnyParams,
+        ) -> Result<Box<dyn $crate::solution::Solver + 'a>, $crate::parser::ParseError> {
+            let params = params.get::<$day::Params>();
+            Ok(Box::new($crate::solution::Parsed {
+                input: $day::parse(input, params)?,
+                params: params.clone(),
+                part1: $day::part1,
+                part2: $day::part2,
+            }))
+        }
+        $crate::solution::Solution {
+            parse,
+            params: |overrides| {
+                $crate::params::with_overrides::<$day::Params>(overrides)
+                    .map($crate::params::AnyParams::new)
+            },
+            examples: $day::EXAMPLES,
+        }
+    }};
+}
+
+/// Declares each day's module and `SOLUTIONS` from them in order, with a test checking
+/// each day's examples.
+macro_rules! solutions {
+    ($($day:ident $($word:ident)*),* $(,)?) => {
+        $(pub mod $day;)*
+
+        /// Every day's solution, indexed by day - 1.
+        pub const SOLUTIONS: &[$crate::solution::Solution] =
+            &[$($crate::solution::solution!($day $($word)*)),*];
+
+        #[cfg(test)]
+        mod example_tests {
+            use super::*;
+
+            $(
+                #[test]
+                fn $day() {
+                    let failures = $crate::solution::check_examples(
+                        &$crate::solution::solution!($day $($word)*),
+                    );
+                    assert!(failures.is_empty(), "{}", failures.join("\n"));
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use solution;
+pub(crate) use solutions;
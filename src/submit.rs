@@ -0,0 +1,337 @@
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::json::{self, Value};
+
+/// Where submitted answers are recorded, next to the inputs they were computed from.
+pub fn history_path() -> PathBuf {
+    PathBuf::from("inputs/submissions.jsonl")
+}
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Rejected without checking because the last answer was too recent
+    TooSoon,
+    /// The part isn't the one being solved, usually because it's already done
+    WrongLevel,
+    /// A response we couldn't make sense of
+    Unknown,
+}
+
+const VERDICTS: [(Verdict, &str); 7] = [
+    (Verdict::Correct, "correct"),
+    (Verdict::Wrong, "wrong"),
+    (Verdict::TooHigh, "too high"),
+    (Verdict::TooLow, "too low"),
+    (Verdict::TooSoon, "too soon"),
+    (Verdict::WrongLevel, "wrong level"),
+    (Verdict::Unknown, "unknown"),
+];
+
+impl Verdict {
+    fn is_wrong(self) -> bool {
+        matches!(self, Verdict::Wrong | Verdict::TooHigh | Verdict::TooLow)
+    }
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (_, name) = VERDICTS.iter().find(|(v, _)| v == self).unwrap();
+        write!(f, "{}", name)
+    }
+}
+
+/// A parsed answer response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Response {
+    pub verdict: Verdict,
+    /// Seconds to wait before answering again, if the site said
+    pub wait: Option<u64>,
+}
+
+/// Make sense of the page returned after submitting an answer.
+pub fn parse_response(page: &str) -> Response {
+    static LEFT_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"You have (?:(\d+)m )?(\d+)s left to wait").unwrap());
+    static PLEASE_REGEX: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?i)please wait (one|\d+) minutes?").unwrap());
+
+    let verdict = if page.contains("That's the right answer") {
+        Verdict::Correct
+    } else if page.contains("That's not the right answer") {
+        if page.contains("your answer is too high") {
+            Verdict::TooHigh
+        } else if page.contains("your answer is too low") {
+            Verdict::TooLow
+        } else {
+            Verdict::Wrong
+        }
+    } else if page.contains("You gave an answer too recently") {
+        Verdict::TooSoon
+    } else if page.contains("You don't seem to be solving the right level") {
+        Verdict::WrongLevel
+    } else {
+        Verdict::Unknown
+    };
+
+    let wait = if let Some(caps) = LEFT_REGEX.captures(page) {
+        let minutes: u64 = caps.get(1).map_or(0, |m| m.as_str().parse().unwrap());
+        let seconds: u64 = caps[2].parse().unwrap();
+        Some(minutes * 60 + seconds)
+    } else {
+        PLEASE_REGEX.captures(page).map(|caps| match &caps[1] {
+            "one" => 60,
+            minutes => minutes.parse::<u64>().unwrap() * 60,
+        })
+    };
+
+    Response { verdict, wait }
+}
+
+/// One submitted answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub day: usize,
+    pub part: u8,
+    pub answer: String,
+    pub response: Response,
+    /// Seconds since the Unix epoch
+    pub time: u64,
+}
+
+impl Attempt {
+    fn to_json(&self) -> Value {
+        Value::Object(vec![
+            ("day".to_string(), Value::Number(self.day as f64)),
+            ("part".to_string(), Value::Number(self.part as f64)),
+            ("answer".to_string(), Value::from(self.answer.as_str())),
+            (
+                "verdict".to_string(),
+                Value::from(self.response.verdict.to_string()),
+            ),
+            (
+                "wait".to_string(),
+                Value::from(self.response.wait.map(|wait| wait as f64)),
+            ),
+            ("time".to_string(), Value::Number(self.time as f64)),
+        ])
+    }
+
+    fn from_json(value: &Value) -> Option<Attempt> {
+        let number = |key| value.get(key).and_then(Value::as_f64);
+        let verdict = value.get("verdict").and_then(Value::as_str)?;
+        Some(Attempt {
+            day: number("day")? as usize,
+            part: number("part")? as u8,
+            answer: value.get("answer").and_then(Value::as_str)?.to_string(),
+            response: Response {
+                verdict: VERDICTS.iter().find(|(_, name)| *name == verdict)?.0,
+                wait: number("wait").map(|wait| wait as u64),
+            },
+            time: number("time")? as u64,
+        })
+    }
+}
+
+/// Every answer submitted so far, stored one JSON object per line.
+#[derive(Debug, Default)]
+pub struct History {
+    pub attempts: Vec<Attempt>,
+}
+
+impl History {
+    /// Read the history at `path`, which is empty if the file doesn't exist yet.
+    pub fn load(path: &Path) -> Result<History, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(History::default()),
+            Err(err) => return Err(err.to_string()),
+        };
+
+        let attempts = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                json::parse(line)
+                    .ok()
+                    .as_ref()
+                    .and_then(Attempt::from_json)
+                    .ok_or_else(|| format!("line {}: invalid attempt", i + 1))
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(History { attempts })
+    }
+
+    /// Add an attempt, appending it to the file at `path` too.
+    pub fn record(&mut self, path: &Path, attempt: Attempt) -> Result<(), String> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|err| err.to_string())?;
+        writeln!(file, "{}", attempt.to_json()).map_err(|err| err.to_string())?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    /// Explain why submitting `answer` at `now` would be pointless, if it would be.
+    pub fn check(&self, day: usize, part: u8, answer: &str, now: u64) -> Result<(), String> {
+        // The site limits how often anything can be submitted, not just this part
+        if let Some(until) = self
+            .attempts
+            .iter()
+            .filter_map(|a| a.response.wait.map(|wait| a.time + wait))
+            .max()
+            .filter(|&until| until > now)
+        {
+            return Err(format!("Answered too recently, wait {}s", until - now));
+        }
+
+        let number = answer.parse::<i64>().ok();
+        for attempt in self
+            .attempts
+            .iter()
+            .filter(|a| a.day == day && a.part == part)
+        {
+            let verdict = attempt.response.verdict;
+            let previous = attempt.answer.parse::<i64>().ok();
+            match (verdict, number, previous) {
+                (Verdict::Correct, _, _) => {
+                    return Err(format!("Already solved with {}", attempt.answer))
+                }
+                _ if verdict.is_wrong() && attempt.answer == answer => {
+                    return Err(format!("{} was already {}", answer, verdict))
+                }
+                (Verdict::TooHigh, Some(n), Some(bound)) if n >= bound => {
+                    return Err(format!("{} is too high, {} already was", n, bound))
+                }
+                (Verdict::TooLow, Some(n), Some(bound)) if n <= bound => {
+                    return Err(format!("{} is too low, {} already was", n, bound))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::client::{serve_once, Client};
+
+    #[test]
+    fn test_parse_response() {
+        let wrong = "<article><p>That's not the right answer; your answer is too high. \
+                     Please wait one minute before trying again.</p></article>";
+        assert_eq!(
+            parse_response(wrong),
+            Response {
+                verdict: Verdict::TooHigh,
+                wait: Some(60)
+            }
+        );
+
+        let soon = "You gave an answer too recently; you have to wait after submitting an \
+                    answer before trying again.  You have 1m 12s left to wait.";
+        assert_eq!(
+            parse_response(soon),
+            Response {
+                verdict: Verdict::TooSoon,
+                wait: Some(72)
+            }
+        );
+
+        assert_eq!(
+            parse_response("That's the right answer! You are one gold star closer.").verdict,
+            Verdict::Correct
+        );
+    }
+
+    #[test]
+    fn test_check() {
+        let attempt = |answer: &str, verdict, time| Attempt {
+            day: 1,
+            part: 1,
+            answer: answer.to_string(),
+            response: Response {
+                verdict,
+                wait: Some(60),
+            },
+            time,
+        };
+        let history = History {
+            attempts: vec![
+                attempt("100", Verdict::TooHigh, 0),
+                attempt("10", Verdict::TooLow, 100),
+                attempt("50", Verdict::Wrong, 200),
+            ],
+        };
+
+        assert_eq!(
+            history.check(1, 1, "60", 230),
+            Err("Answered too recently, wait 30s".to_string())
+        );
+        assert!(history.check(1, 1, "60", 300).is_ok());
+        assert!(history.check(1, 1, "50", 300).is_err());
+        assert!(history.check(1, 1, "150", 300).is_err());
+        assert!(history.check(1, 1, "5", 300).is_err());
+        assert!(history.check(1, 2, "150", 300).is_ok());
+
+        let round_trip = Attempt::from_json(&attempt("50", Verdict::Wrong, 200).to_json());
+        assert_eq!(round_trip, Some(attempt("50", Verdict::Wrong, 200)));
+    }
+
+    #[test]
+    fn test_submit_to_stub() {
+        let (url, server) = serve_once(
+            "<article><p>That's not the right answer; your answer is too low. \
+             Please wait one minute before trying again.</p></article>",
+        );
+        let client = Client::new(&url, "abc123");
+        let dir = env::temp_dir().join(format!("aoc24-submit-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("submissions.jsonl");
+
+        let mut history = History::load(&path).unwrap();
+        assert!(history.check(17, 1, "4,6,3", 1000).is_ok());
+        let response = parse_response(&client.submit(17, 1, "4,6,3").unwrap());
+        let attempt = Attempt {
+            day: 17,
+            part: 1,
+            answer: "4,6,3".to_string(),
+            response,
+            time: 1000,
+        };
+        history.record(&path, attempt.clone()).unwrap();
+
+        let (headers, body) = server.join().unwrap();
+        assert_eq!(headers[0], "POST /2024/day/17/answer HTTP/1.1");
+        assert!(headers.contains(&"Cookie: session=abc123".to_string()));
+        assert_eq!(body, "level=1&answer=4%2C6%2C3");
+
+        assert_eq!(
+            response,
+            Response {
+                verdict: Verdict::TooLow,
+                wait: Some(60)
+            }
+        );
+        assert_eq!(History::load(&path).unwrap().attempts, [attempt]);
+        assert!(history.check(17, 1, "4,6,3", 1030).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
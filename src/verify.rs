@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+
+use crate::answer::Answer;
+use crate::runner::{print_table, Outcome};
+
+/// Known-correct answers, keyed by day and part.
+///
+/// Read from a small TOML subset:
+///
+/// ```toml
+/// # Comments and blank lines are ignored
+/// [day01]
+/// part1 = 1234
+/// part2 = "abc,def"
+/// ```
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct Expected {
+    answers: BTreeMap<(usize, u8), String>,
+}
+
+impl Expected {
+    pub fn parse(text: &str) -> Result<Expected, String> {
+        let mut expected = Expected::default();
+        let mut day = None;
+
+        for (i, line) in text.lines().enumerate() {
+            let error = |message: &str| format!("line {}: {}: {}", i + 1, message, line.trim());
+            let line = strip_comment(line).trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some(header) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let number = header.trim().strip_prefix("day").unwrap_or(header.trim());
+                day = Some(
+                    number
+                        .parse::<usize>()
+                        .map_err(|_| error("Invalid day header"))?,
+                );
+                continue;
+            }
+
+            let day = day.ok_or_else(|| error("Answer outside of a [dayNN] section"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("Expected `partN = value`"))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(error("Expected part1 or part2")),
+            };
+            let value = parse_value(value.trim()).ok_or_else(|| error("Invalid value"))?;
+
+            if expected.answers.insert((day, part), value).is_some() {
+                return Err(error("Duplicate answer"));
+            }
+        }
+
+        Ok(expected)
+    }
+
+    pub fn get(&self, day: usize, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(String::as_str)
+    }
+
+    pub fn has_day(&self, day: usize) -> bool {
+        self.answers.keys().any(|&(d, _)| d == day)
+    }
+}
+
+/// Remove a trailing `#` comment, ignoring any `#` inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '#' if !in_string => return &line[..i],
+            _ => {}
+        }
+    }
+    line
+}
+
+/// An integer or a basic double-quoted string.
+fn parse_value(value: &str) -> Option<String> {
+    if let Some(inner) = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        let mut s = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next()? {
+                    '"' => s.push('"'),
+                    '\\' => s.push('\\'),
+                    'n' => s.push('\n'),
+                    't' => s.push('\t'),
+                    _ => return None,
+                },
+                '"' => return None,
+                c => s.push(c),
+            }
+        }
+        Some(s)
+    } else {
+        // TOML allows underscores as digit separators
+        let digits = value.replace('_', "");
+        digits.parse::<i64>().ok().map(|n| n.to_string())
+    }
+}
+
+/// How a computed answer compares with the expected one.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// No expected answer is recorded
+    Missing,
+}
+
+pub fn check(expected: Option<&str>, answer: &Answer) -> Status {
+    match expected {
+        None => Status::Missing,
+        Some(expected) if *answer != Answer::Unsolved && answer.to_string() == expected => {
+            Status::Pass
+        }
+        Some(_) => Status::Fail,
+    }
+}
+
+/// Print a pass/fail table, returning the number of failed parts.
+pub fn report(expected: &Expected, outcomes: &[(usize, Outcome)]) -> usize {
+    let mut rows = vec![["Day", "Part", "Status", "Answer", "Expected"]
+        .map(String::from)
+        .to_vec()];
+    let mut counts = [0; 3];
+
+    for (day, outcome) in outcomes {
+        let report = match outcome {
+            Outcome::Ran(report) => report,
+            Outcome::Skipped(reason) => {
+                // Put the reason last so it doesn't widen the other columns
+                rows.push(vec![
+                    day.to_string(),
+                    String::new(),
+                    String::from("skipped"),
+                    String::new(),
+                    String::new(),
+                    format!("({})", reason),
+                ]);
+                continue;
+            }
+            Outcome::Failed(error) => {
+                counts[1] += 1;
+                rows.push(vec![
+                    day.to_string(),
+                    String::new(),
+                    String::from("FAIL"),
+                    String::new(),
+                    String::new(),
+                    format!("({})", error),
+                ]);
+                continue;
+            }
+        };
+
+        for (part, result) in [(1, &report.part1), (2, &report.part2)] {
+            let Some((answer, _)) = result else {
+                continue;
+            };
+            let want = expected.get(*day, part);
+            let status = check(want, answer);
+
+            let (label, index) = match status {
+                Status::Pass => ("pass", 0),
+                Status::Fail => ("FAIL", 1),
+                Status::Missing => ("missing", 2),
+            };
+            counts[index] += 1;
+
+            rows.push(vec![
+                day.to_string(),
+                part.to_string(),
+                label.to_string(),
+                answer.to_string(),
+                want.unwrap_or("").to_string(),
+            ]);
+        }
+    }
+
+    print_table(&rows, &[2, 3, 4, 5]);
+    println!(
+        "{} passed, {} failed, {} missing",
+        counts[0], counts[1], counts[2]
+    );
+
+    counts[1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let text = "\
+# Our answers
+[day01]
+part1 = 1_234 # trailing comment
+part2 = \"co,de # not a comment\"
+
+[17]
+part2 = -5
+";
+        let expected = Expected::parse(text).unwrap();
+
+        assert_eq!(expected.get(1, 1), Some("1234"));
+        assert_eq!(expected.get(1, 2), Some("co,de # not a comment"));
+        assert_eq!(expected.get(17, 1), None);
+        assert_eq!(expected.get(17, 2), Some("-5"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Expected::parse("part1 = 1").is_err());
+        assert!(Expected::parse("[day01]\npart3 = 1").is_err());
+        assert!(Expected::parse("[day01]\npart1 = abc").is_err());
+        assert!(Expected::parse("[day01]\npart1 = 1\npart1 = 2").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(Some("11"), &Answer::Int(11)), Status::Pass);
+        assert_eq!(check(Some("6,1"), &Answer::Coord(6, 1)), Status::Pass);
+        assert_eq!(check(Some("12"), &Answer::Int(11)), Status::Fail);
+        assert_eq!(check(Some("unsolved"), &Answer::Unsolved), Status::Fail);
+        assert_eq!(check(None, &Answer::Int(11)), Status::Missing);
+    }
+}